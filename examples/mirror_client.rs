@@ -0,0 +1,69 @@
+//! Watch a device's screen in the terminal and drive it from the keyboard.
+//!
+//! Start the device with `gui.serve_mirror("127.0.0.1:7878")`, forward the port
+//! with `ssh -L 7878:127.0.0.1:7878 <device>`, then run
+//! `cargo run --example mirror_client`. Type any of `a b c u d l r` and press
+//! enter to press those buttons.
+
+use std::env;
+use std::io::{self, BufRead, Read, Write};
+use std::net::TcpStream;
+use std::thread;
+
+use tinygui::mirror::{run_length_decode, MAGIC};
+
+fn main() -> io::Result<()> {
+    let address = env::args().nth(1).unwrap_or_else(|| "127.0.0.1:7878".to_owned());
+    let mut stream = TcpStream::connect(&address)?;
+
+    let mut header = [0; 8];
+    stream.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        eprintln!("{} is not a mirror server", address);
+        return Ok(());
+    }
+    let width = u16::from_be_bytes([header[4], header[5]]) as usize;
+    let height = u16::from_be_bytes([header[6], header[7]]) as usize;
+    let row_bytes = width.div_ceil(8);
+
+    // forward typed keys as button presses
+    let mut keys = stream.try_clone()?;
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = line.expect("Could not read stdin");
+            if keys.write_all(line.trim().as_bytes()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut frame = vec![0u8; row_bytes * height];
+    loop {
+        let mut length = [0; 4];
+        stream.read_exact(&mut length)?;
+        let mut encoded = vec![0; u32::from_be_bytes(length) as usize];
+        stream.read_exact(&mut encoded)?;
+        let delta = run_length_decode(&encoded)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "corrupt frame"))?;
+        for (byte, delta) in frame.iter_mut().zip(delta) {
+            *byte ^= delta;
+        }
+
+        // two rows of pixels to each line of text
+        let lit = |x: usize, y: usize| y < height && frame[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0;
+        let mut out = String::from("\x1b[H\x1b[2J");
+        for y in (0..height).step_by(2) {
+            for x in 0..width {
+                out.push(match (lit(x, y), lit(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                });
+            }
+            out.push('\n');
+        }
+        print!("{}", out);
+        io::stdout().flush()?;
+    }
+}
@@ -6,7 +6,8 @@ const TICK_DURATION: u128 = 300;
 
 /// Struct representing a single button
 pub struct Button {
-    pin: Box<InputPin>,
+    /// `None` for a virtual button, which is only pressed through `press`
+    pin: Option<Box<InputPin>>,
     last_state: Level,
     last_pressed: Option<SystemTime>,
     last_ticked: Option<SystemTime>,
@@ -18,7 +19,7 @@ impl Button {
     /// Create a new button off a given pin
     pub fn new(pin: InputPin) -> Button {
         Button {
-            pin: Box::new(pin),
+            pin: Some(Box::new(pin)),
             last_state: Level::Low,
             last_pressed: None,
             last_ticked: None,
@@ -27,10 +28,31 @@ impl Button {
         }
     }
 
+    /// Create a button with no pin behind it, pressed only by calling `press`
+    pub fn virtual_button() -> Button {
+        Button {
+            pin: None,
+            last_state: Level::Low,
+            last_pressed: None,
+            last_ticked: None,
+            is_hold: false,
+            has_been_pressed: false
+        }
+    }
+
+    /// Register a press, as if the button had been pushed and released
+    pub fn press(&mut self) {
+        self.has_been_pressed = true;
+    }
+
     /// Check whether the internal value of a button should be updated
     /// based on the pin values
     pub fn poll(&mut self) {
-        if self.pin.is_low() {
+        let pin = match &self.pin {
+            Some(pin) => pin,
+            None => return,
+        };
+        if pin.is_low() {
             if self.last_state == Level::Low {
                 self.last_pressed = Some(SystemTime::now());
                 self.has_been_pressed = true;
@@ -59,7 +81,7 @@ impl Button {
                 }
                 None => { }
             }
-        } else if self.pin.is_high() {
+        } else if pin.is_high() {
             self.last_state = Level::Low;
             self.last_ticked = None;
             self.last_pressed = None;
@@ -79,6 +101,12 @@ impl Button {
 
     /// Whether the button is being held.
     pub fn is_held(&self) -> bool { self.is_hold }
+
+    /// Whether the button is physically down, as of the last poll
+    pub fn is_down(&self) -> bool { self.last_state == Level::High }
+
+    /// Whether there is a press waiting, without consuming it
+    pub fn has_press(&self) -> bool { self.has_been_pressed }
 }
 
 pub struct ButtonSet {
@@ -102,6 +130,20 @@ impl ButtonSet {
         self.right.poll();
     }
 
+    /// Whether any button has a press waiting, without consuming it
+    pub fn any_pressed(&self) -> bool {
+        self.buttons().iter().any(|button| button.has_press())
+    }
+
+    /// Whether any button is physically down
+    pub fn any_down(&self) -> bool {
+        self.buttons().iter().any(|button| button.is_down())
+    }
+
+    fn buttons(&self) -> Vec<&Button> {
+        vec![&self.a, &self.b, &self.c, &self.up, &self.down, &self.left, &self.right]
+    }
+
     pub fn all_buttons(&mut self) -> Vec<&mut Button> {
         vec![
             &mut self.a,
@@ -121,6 +163,19 @@ impl ButtonSet {
             .into_input_pullup());
     }
 
+    /// Buttons with no pins behind them, for driving a headless GUI
+    pub fn virtual_buttons() -> ButtonSet {
+        ButtonSet {
+            a: Button::virtual_button(),
+            b: Button::virtual_button(),
+            c: Button::virtual_button(),
+            up: Button::virtual_button(),
+            down: Button::virtual_button(),
+            left: Button::virtual_button(),
+            right: Button::virtual_button()
+        }
+    }
+
     pub fn default_pins() -> ButtonSet {
         ButtonSet {
             a: ButtonSet::get_button(5),
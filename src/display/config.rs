@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::display::{Controller, DisplayError, PanelSize};
+use crate::shape::Rotation;
+
+/// How the display is wired up, usually read from a JSON file such as
+///
+/// ```json
+/// {
+///     "controller": "sh1106",
+///     "panel": "128x64",
+///     "rotation": 180,
+///     "interface": { "type": "i2c", "bus": 1, "address": 61 }
+/// }
+/// ```
+///
+/// Every field is optional; the defaults describe an SSD1306 128x64 panel on I2C bus 1
+/// at address 0x3C. SPI panels use `{ "type": "spi", "dc_pin": 24, "reset_pin": 25 }`,
+/// and `{ "type": "headless" }` runs without a panel.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub controller: Controller,
+    pub panel: PanelSize,
+    /// Clockwise rotation in degrees: 0, 90, 180 or 270
+    pub rotation: u16,
+    pub interface: InterfaceConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum InterfaceConfig {
+    I2c {
+        #[serde(default = "default_i2c_bus")]
+        bus: u8,
+        /// 7-bit address, written in decimal since JSON has no hex literals
+        #[serde(default = "default_i2c_address")]
+        address: u8,
+    },
+    Spi {
+        #[serde(default)]
+        bus: u8,
+        #[serde(default)]
+        slave_select: u8,
+        #[serde(default = "default_spi_clock")]
+        clock_speed: u32,
+        /// BCM number of the data/command pin
+        dc_pin: u8,
+        #[serde(default)]
+        reset_pin: Option<u8>,
+    },
+    Headless,
+}
+
+fn default_i2c_bus() -> u8 { 1 }
+fn default_i2c_address() -> u8 { 0x3c }
+fn default_spi_clock() -> u32 { 8_000_000 }
+
+impl Default for InterfaceConfig {
+    fn default() -> InterfaceConfig {
+        InterfaceConfig::I2c { bus: default_i2c_bus(), address: default_i2c_address() }
+    }
+}
+
+impl DisplayConfig {
+    pub fn from_json(source: &str) -> Result<DisplayConfig, DisplayError> {
+        let config: DisplayConfig = serde_json::from_str(source)
+            .map_err(|err| DisplayError::Config(err.to_string()))?;
+        config.rotation()?;
+        Ok(config)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<DisplayConfig, DisplayError> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|err| DisplayError::Config(err.to_string()))?;
+        DisplayConfig::from_json(&source)
+    }
+
+    pub fn rotation(&self) -> Result<Rotation, DisplayError> {
+        match self.rotation {
+            0 => Ok(Rotation::Deg0),
+            90 => Ok(Rotation::Deg90),
+            180 => Ok(Rotation::Deg180),
+            270 => Ok(Rotation::Deg270),
+            degrees => Err(DisplayError::Config(format!("can't rotate by {} degrees", degrees))),
+        }
+    }
+}
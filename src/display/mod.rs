@@ -0,0 +1,315 @@
+mod config;
+
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+use rppal::gpio::Gpio;
+use rppal::i2c::I2c;
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
+use serde::Deserialize;
+use ssd1306::interface::{DisplayInterface, I2cInterface, SpiInterface};
+
+pub use config::{DisplayConfig, InterfaceConfig};
+
+#[derive(Debug)]
+pub enum DisplayError {
+    Config(String),
+    Bus(String),
+}
+
+impl fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DisplayError::Config(msg) => write!(f, "invalid display config: {}", msg),
+            DisplayError::Bus(msg) => write!(f, "could not talk to display: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DisplayError {}
+
+fn bus_error<E: fmt::Debug>(err: E) -> DisplayError {
+    DisplayError::Bus(format!("{:?}", err))
+}
+
+/// The panel resolutions a `Screen` can drive
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize)]
+pub enum PanelSize {
+    #[default]
+    #[serde(rename = "128x64")]
+    Panel128x64,
+    #[serde(rename = "128x32")]
+    Panel128x32,
+    #[serde(rename = "96x16")]
+    Panel96x16,
+}
+
+impl PanelSize {
+    /// Width and height of the panel in its natural orientation
+    pub fn dimensions(&self) -> (i32, i32) {
+        match self {
+            PanelSize::Panel128x64 => (128, 64),
+            PanelSize::Panel128x32 => (128, 32),
+            PanelSize::Panel96x16 => (96, 16),
+        }
+    }
+}
+
+/// The display controller chip, which decides how the panel is set up and written to
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Controller {
+    #[default]
+    Ssd1306,
+    /// 1.3" panels; 132 columns of RAM with the visible 128 starting at column 2
+    Sh1106,
+}
+
+/// A bus the display's commands and pixel data are sent over
+pub trait Interface {
+    /// Send one command along with its arguments
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), DisplayError>;
+    fn send_data(&mut self, data: &[u8]) -> Result<(), DisplayError>;
+}
+
+impl<T, E> Interface for T
+where
+    T: DisplayInterface<Error = E>,
+    E: fmt::Debug,
+{
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), DisplayError> {
+        // the ssd1306 I2C interface writes a control byte and the commands from an 8 byte buffer
+        if commands.len() > 7 {
+            return Err(DisplayError::Bus(format!("{} command bytes sent at once, at most 7 fit", commands.len())));
+        }
+        DisplayInterface::send_commands(self, commands).map_err(bus_error)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), DisplayError> {
+        DisplayInterface::send_data(self, data).map_err(bus_error)
+    }
+}
+
+/// Discards everything, for running without a panel attached
+pub struct Headless;
+
+impl Interface for Headless {
+    fn send_commands(&mut self, _commands: &[u8]) -> Result<(), DisplayError> { Ok(()) }
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), DisplayError> { Ok(()) }
+}
+
+/// A display controller connected over some interface
+pub struct Display {
+    controller: Controller,
+    panel: PanelSize,
+    interface: Box<dyn Interface>,
+}
+
+impl Display {
+    pub fn new(controller: Controller, panel: PanelSize, interface: Box<dyn Interface>) -> Display {
+        Display { controller, panel, interface }
+    }
+
+    /// Open the bus described by `config`
+    pub fn connect(config: &DisplayConfig) -> Result<Display, DisplayError> {
+        let interface: Box<dyn Interface> = match config.interface {
+            InterfaceConfig::I2c { bus, address } => {
+                let i2c = I2c::with_bus(bus).map_err(bus_error)?;
+                Box::new(I2cInterface::new(i2c, address))
+            }
+            InterfaceConfig::Spi { bus, slave_select, clock_speed, dc_pin, reset_pin } => {
+                let bus = match bus {
+                    0 => Bus::Spi0,
+                    1 => Bus::Spi1,
+                    2 => Bus::Spi2,
+                    _ => return Err(DisplayError::Config(format!("no SPI bus {}", bus))),
+                };
+                let slave_select = match slave_select {
+                    0 => SlaveSelect::Ss0,
+                    1 => SlaveSelect::Ss1,
+                    2 => SlaveSelect::Ss2,
+                    _ => return Err(DisplayError::Config(format!("no slave select {}", slave_select))),
+                };
+                let spi = Spi::new(bus, slave_select, clock_speed, Mode::Mode0).map_err(bus_error)?;
+                let gpio = Gpio::new().map_err(bus_error)?;
+                let dc = gpio.get(dc_pin).map_err(bus_error)?.into_output();
+                if let Some(reset_pin) = reset_pin {
+                    // pulse reset low, then leave it high once the pin is dropped
+                    let mut reset = gpio.get(reset_pin).map_err(bus_error)?.into_output();
+                    reset.set_reset_on_drop(false);
+                    reset.set_low();
+                    thread::sleep(Duration::from_millis(10));
+                    reset.set_high();
+                }
+                Box::new(SpiInterface::new(spi, dc))
+            }
+            InterfaceConfig::Headless => Box::new(Headless),
+        };
+        Ok(Display::new(config.controller, config.panel, interface))
+    }
+
+    pub fn panel(&self) -> PanelSize { self.panel }
+
+    /// Send the controller's power-on sequence
+    pub fn init(&mut self) -> Result<(), DisplayError> {
+        let (_, height) = self.panel.dimensions();
+        let com_pins = if height == 64 { 0x12 } else { 0x02 };
+        match self.controller {
+            Controller::Ssd1306 => self.send_each(&[
+                &[0xAE],                    // display off
+                &[0xD5, 0x80],              // clock divide ratio
+                &[0xA8, height as u8 - 1],  // multiplex ratio
+                &[0xD3, 0x00],              // display offset
+                &[0x40],                    // start line 0
+                &[0x8D, 0x14],              // charge pump on
+                &[0x20, 0x00],              // horizontal addressing
+                &[0xA1],                    // column 127 is segment 0
+                &[0xC8],                    // scan rows from the bottom
+                &[0xDA, com_pins],
+                &[0x81, 0x8F],              // contrast
+                &[0xD9, 0xF1],              // pre-charge period
+                &[0xDB, 0x40],              // VCOMH deselect level
+                &[0xA4],                    // show RAM contents
+                &[0xA6],                    // not inverted
+                &[0xAF],                    // display on
+            ]),
+            Controller::Sh1106 => self.send_each(&[
+                &[0xAE],
+                &[0xD5, 0x80],
+                &[0xA8, height as u8 - 1],
+                &[0xD3, 0x00],
+                &[0x40],
+                &[0xAD, 0x8B],              // DC-DC converter on
+                &[0x32],                    // pump voltage 8.0V
+                &[0xA1],
+                &[0xC8],
+                &[0xDA, com_pins],
+                &[0x81, 0x80],
+                &[0xD9, 0x22],
+                &[0xDB, 0x35],
+                &[0xA4],
+                &[0xA6],
+                &[0xAF],
+            ]),
+        }
+    }
+
+    fn send_each(&mut self, commands: &[&[u8]]) -> Result<(), DisplayError> {
+        for command in commands {
+            self.interface.send_commands(command)?;
+        }
+        Ok(())
+    }
+
+    /// Panel brightness, from 0 to 255
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError> {
+        self.interface.send_commands(&[0x81, contrast])
+    }
+
+    /// Light the unlit pixels and vice versa, without touching the frame
+    pub fn set_inverted(&mut self, inverted: bool) -> Result<(), DisplayError> {
+        self.interface.send_commands(&[if inverted { 0xA7 } else { 0xA6 }])
+    }
+
+    /// Switch the panel on or off. The controller keeps its memory while off.
+    pub fn set_on(&mut self, on: bool) -> Result<(), DisplayError> {
+        self.interface.send_commands(&[if on { 0xAF } else { 0xAE }])
+    }
+
+    /// Write a whole frame. `buffer` holds one byte per column for each 8 pixel tall page,
+    /// with the top pixel in the least significant bit.
+    pub fn flush(&mut self, buffer: &[u8]) -> Result<(), DisplayError> {
+        let (width, height) = self.panel.dimensions();
+        let pages = height / 8;
+        match self.controller {
+            Controller::Ssd1306 => {
+                self.send_each(&[
+                    &[0x21, 0, width as u8 - 1],    // column range
+                    &[0x22, 0, pages as u8 - 1],    // page range
+                ])?;
+                self.interface.send_data(buffer)
+            }
+            Controller::Sh1106 => {
+                // no horizontal addressing, so each page is written separately
+                let offset = 2;
+                for (page, row) in buffer.chunks(width as usize).enumerate() {
+                    self.send_each(&[
+                        &[0xB0 + page as u8],
+                        &[offset & 0x0F],
+                        &[0x10 | (offset >> 4)],
+                    ])?;
+                    self.interface.send_data(row)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        commands: Rc<RefCell<Vec<Vec<u8>>>>,
+        data: Rc<RefCell<Vec<u8>>>,
+    }
+
+    // behaves like the ssd1306 I2C interface, which panics on more than 7 command bytes
+    impl DisplayInterface for Recorder {
+        type Error = ();
+
+        fn send_commands(&mut self, cmds: &[u8]) -> Result<(), ()> {
+            let mut writebuf = [0u8; 8];
+            writebuf[1..=cmds.len()].copy_from_slice(cmds);
+            self.commands.borrow_mut().push(cmds.to_vec());
+            Ok(())
+        }
+
+        fn send_data(&mut self, buf: &[u8]) -> Result<(), ()> {
+            self.data.borrow_mut().extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    fn run(controller: Controller, panel: PanelSize) -> Recorder {
+        let recorder = Recorder::default();
+        let mut display = Display::new(controller, panel, Box::new(recorder.clone()));
+        let (width, height) = panel.dimensions();
+        display.init().expect("init should succeed");
+        display.flush(&vec![0xFF; (width * height / 8) as usize]).expect("flush should succeed");
+        recorder
+    }
+
+    #[test]
+    fn ssd1306_sends_whole_commands() {
+        let recorder = run(Controller::Ssd1306, PanelSize::Panel128x32);
+        let commands = recorder.commands.borrow();
+        assert!(commands.contains(&vec![0xA8, 31]));
+        assert!(commands.contains(&vec![0xDA, 0x02]));
+        assert_eq!(commands[commands.len() - 2..], [vec![0x21, 0, 127], vec![0x22, 0, 3]]);
+        assert_eq!(recorder.data.borrow().len(), 128 * 32 / 8);
+    }
+
+    #[test]
+    fn sh1106_writes_each_page() {
+        let recorder = run(Controller::Sh1106, PanelSize::Panel128x64);
+        let commands = recorder.commands.borrow();
+        assert!(commands.contains(&vec![0xAD, 0x8B]));
+        for page in 0..8 {
+            assert!(commands.contains(&vec![0xB0 + page]));
+        }
+        assert_eq!(recorder.data.borrow().len(), 128 * 64 / 8);
+    }
+
+    #[test]
+    fn rejects_commands_too_long_for_one_write() {
+        let mut recorder = Recorder::default();
+        assert!(matches!(Interface::send_commands(&mut recorder, &[0; 8]), Err(DisplayError::Bus(_))));
+        assert!(recorder.commands.borrow().is_empty());
+    }
+}
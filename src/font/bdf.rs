@@ -0,0 +1,213 @@
+use std::fmt;
+use std::io;
+
+use crate::font::{BitmapFont, Glyph};
+
+// far bigger than any glyph that fits on the screen, and small enough that a corrupt
+// file can't make us allocate much
+const MAX_GLYPH_SIZE: i32 = 255;
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    Parse(usize, String),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FontError::Io(err) => write!(f, "could not read font: {}", err),
+            FontError::Parse(line, msg) => write!(f, "invalid BDF on line {}: {}", line, msg),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<io::Error> for FontError {
+    fn from(err: io::Error) -> FontError { FontError::Io(err) }
+}
+
+struct PendingGlyph {
+    encoding: i64,
+    advance: Option<i32>,
+    bbx: Option<(i32, i32, i32, i32)>,
+    rows: Vec<Vec<u8>>,
+    in_bitmap: bool,
+}
+
+fn numbers(line: usize, args: &[&str], count: usize) -> Result<Vec<i32>, FontError> {
+    if args.len() < count {
+        return Err(FontError::Parse(line, format!("expected {} values", count)));
+    }
+    args.iter()
+        .take(count)
+        .map(|arg| arg.parse::<i32>()
+             .map_err(|_| FontError::Parse(line, format!("'{}' is not a number", arg))))
+        .collect()
+}
+
+fn hex_row(line: usize, row: &str) -> Result<Vec<u8>, FontError> {
+    if !row.is_ascii() {
+        return Err(FontError::Parse(line, format!("'{}' is not hex", row)));
+    }
+    if !row.len().is_multiple_of(2) {
+        return Err(FontError::Parse(line, "odd number of hex digits".to_owned()));
+    }
+    (0..row.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&row[i..i + 2], 16)
+             .map_err(|_| FontError::Parse(line, format!("'{}' is not hex", row))))
+        .collect()
+}
+
+/// A bounding box: width, height and x and y offsets. The size can't be negative,
+/// or too big to count the pixels of.
+fn bounding_box(line: usize, args: &[&str]) -> Result<(i32, i32, i32, i32), FontError> {
+    let bbx = numbers(line, args, 4)?;
+    let (width, height) = (bbx[0], bbx[1]);
+    if width < 0 || height < 0 {
+        return Err(FontError::Parse(line, "negative bounding box size".to_owned()));
+    }
+    if width > MAX_GLYPH_SIZE || height > MAX_GLYPH_SIZE {
+        return Err(FontError::Parse(line, format!("bounding box larger than {0}x{0}", MAX_GLYPH_SIZE)));
+    }
+    Ok((width, height, bbx[2], bbx[3]))
+}
+
+pub fn parse(source: &str) -> Result<BitmapFont, FontError> {
+    let mut font_box = None;
+    let mut ascent = None;
+    let mut descent = None;
+    let mut default_char = None;
+    let mut glyphs = vec![];
+    let mut pending: Option<PendingGlyph> = None;
+
+    for (i, raw) in source.lines().enumerate() {
+        let line = i + 1;
+        let mut words = raw.split_whitespace();
+        let keyword = match words.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args: Vec<&str> = words.collect();
+
+        if let Some(glyph) = pending.as_mut() {
+            if glyph.in_bitmap && keyword != "ENDCHAR" {
+                glyph.rows.push(hex_row(line, keyword)?);
+                continue;
+            }
+            match keyword {
+                "ENCODING" => glyph.encoding = numbers(line, &args, 1)?[0] as i64,
+                "DWIDTH" => glyph.advance = Some(numbers(line, &args, 1)?[0]),
+                "BBX" => glyph.bbx = Some(bounding_box(line, &args)?),
+                "BITMAP" => glyph.in_bitmap = true,
+                "ENDCHAR" => glyphs.push(pending.take().unwrap()),
+                _ => {}
+            }
+            continue;
+        }
+
+        match keyword {
+            "FONTBOUNDINGBOX" => font_box = Some(bounding_box(line, &args)?),
+            "FONT_ASCENT" => ascent = Some(numbers(line, &args, 1)?[0]),
+            "FONT_DESCENT" => descent = Some(numbers(line, &args, 1)?[0]),
+            "DEFAULT_CHAR" => default_char = Some(numbers(line, &args, 1)?[0]),
+            "STARTCHAR" => pending = Some(PendingGlyph {
+                encoding: -1,
+                advance: None,
+                bbx: None,
+                rows: vec![],
+                in_bitmap: false,
+            }),
+            _ => {}
+        }
+    }
+
+    if pending.is_some() {
+        return Err(FontError::Parse(source.lines().count(), "missing ENDCHAR".to_owned()));
+    }
+    let (box_width, box_height, _, box_y) = font_box
+        .ok_or_else(|| FontError::Parse(0, "missing FONTBOUNDINGBOX".to_owned()))?;
+    let ascent = ascent.unwrap_or(box_height + box_y);
+    let descent = descent.unwrap_or(-box_y);
+
+    let mut font = BitmapFont::new(ascent, descent);
+    for glyph in glyphs {
+        let c = match std::char::from_u32(glyph.encoding as u32) {
+            Some(c) if glyph.encoding >= 0 => c,
+            _ => continue,
+        };
+        let (width, height, x_offset, y_offset) = glyph.bbx.unwrap_or(font_box.unwrap());
+        let mut pixels = vec![false; (width * height) as usize];
+        for (y, row) in glyph.rows.iter().take(height as usize).enumerate() {
+            for x in 0..width as usize {
+                let byte = row.get(x / 8).cloned().unwrap_or(0);
+                pixels[y * width as usize + x] = byte & (0x80 >> (x % 8)) != 0;
+            }
+        }
+        let advance = glyph.advance.unwrap_or(box_width);
+        font.insert(c, Glyph::new(width, height, advance, pixels)
+                    .offset(x_offset, ascent - (height + y_offset)));
+    }
+    if let Some(c) = default_char.and_then(|c| std::char::from_u32(c as u32)) {
+        font.set_default_char(c);
+    }
+    Ok(font)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMAL: &str = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 2 0 0
+STARTCHAR A
+ENCODING 65
+DWIDTH 5 0
+BBX 4 2 0 0
+BITMAP
+90
+60
+ENDCHAR
+ENDFONT
+";
+
+    fn with_line(from: &str, to: &str) -> String {
+        MINIMAL.replace(from, to)
+    }
+
+    #[test]
+    fn parses_minimal_font() {
+        let font = parse(MINIMAL).expect("minimal font should parse");
+        let glyph = font.glyph('A').expect("glyph for A");
+        assert_eq!((glyph.width, glyph.height, glyph.advance), (4, 2, 5));
+        assert!(glyph.is_set(0, 0) && glyph.is_set(3, 0));
+        assert!(glyph.is_set(1, 1) && !glyph.is_set(0, 1));
+    }
+
+    #[test]
+    fn rejects_non_ascii_bitmap_row() {
+        assert!(matches!(parse(&with_line("90\n", "é0\n")), Err(FontError::Parse(8, _))));
+    }
+
+    #[test]
+    fn rejects_negative_glyph_size() {
+        assert!(matches!(parse(&with_line("BBX 4 2", "BBX -4 2")), Err(FontError::Parse(6, _))));
+        assert!(matches!(parse(&with_line("BBX 4 2", "BBX 4 -2")), Err(FontError::Parse(6, _))));
+    }
+
+    #[test]
+    fn rejects_oversized_boxes() {
+        assert!(matches!(parse(&with_line("BBX 4 2", "BBX 65536 65536")), Err(FontError::Parse(6, _))));
+        assert!(matches!(parse(&with_line("BBX 4 2", "BBX 40000 40000")), Err(FontError::Parse(6, _))));
+        assert!(matches!(parse(&with_line("BBX 4 2", "BBX 4 256")), Err(FontError::Parse(6, _))));
+        let font_box = with_line("FONTBOUNDINGBOX 4 2", "FONTBOUNDINGBOX 2147483647 2");
+        assert!(matches!(parse(&font_box), Err(FontError::Parse(2, _))));
+    }
+
+    #[test]
+    fn rejects_missing_endchar() {
+        assert!(parse(&with_line("ENDCHAR\n", "")).is_err());
+    }
+}
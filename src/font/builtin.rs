@@ -0,0 +1,60 @@
+use std::rc::Rc;
+
+use embedded_graphics::fonts::{self, Font};
+use embedded_graphics::pixelcolor::PixelColorU8;
+use embedded_graphics::prelude::*;
+
+use crate::font::{BitmapFont, Glyph};
+
+const TINY_4X6_BDF: &str = include_str!("tiny4x6.bdf");
+const BIG_DIGITS_BDF: &str = include_str!("bigdigits.bdf");
+const LATIN_EXT_6X8_BDF: &str = include_str!("latin_ext6x8.bdf");
+
+thread_local! {
+    pub static TINY_4X6: Rc<BitmapFont> = Rc::new(
+        BitmapFont::from_bdf(TINY_4X6_BDF).expect("Built-in 4x6 font is invalid"));
+    pub static FONT_6X8: Rc<BitmapFont> = Rc::new({
+        let mut font = sample(
+            |s| lit(fonts::Font6x8::render_str(s).with_stroke(Some(PixelColorU8(1))).into_iter()),
+            6, 8, true);
+        font.extend(BitmapFont::from_bdf(LATIN_EXT_6X8_BDF)
+                    .expect("Built-in Latin Extended font is invalid"));
+        font
+    });
+    pub static FONT_6X12: Rc<BitmapFont> = Rc::new(sample(
+        |s| lit(fonts::Font6x12::render_str(s).with_stroke(Some(PixelColorU8(1))).into_iter()),
+        6, 12, false));
+    pub static FONT_8X16: Rc<BitmapFont> = Rc::new(sample(
+        |s| lit(fonts::Font8x16::render_str(s).with_stroke(Some(PixelColorU8(1))).into_iter()),
+        8, 16, true));
+    pub static BIG_DIGITS: Rc<BitmapFont> = Rc::new(
+        BitmapFont::from_bdf(BIG_DIGITS_BDF).expect("Built-in big digit font is invalid"));
+}
+
+fn lit<I: Iterator<Item = Pixel<PixelColorU8>>>(pixels: I) -> Vec<(i32, i32)> {
+    pixels
+        .filter(|Pixel(_, color)| color.into_inner() != 0)
+        .map(|Pixel(coord, _)| (coord[0] as i32, coord[1] as i32))
+        .collect()
+}
+
+/// Build a glyph table from one of embedded-graphics' monospace fonts by rendering
+/// each character it covers once.
+fn sample<F>(render: F, width: i32, height: i32, latin1: bool) -> BitmapFont
+where
+    F: Fn(&str) -> Vec<(i32, i32)>,
+{
+    let mut font = BitmapFont::new(height, 0);
+    // an empty range when the font has no Latin-1 glyphs
+    let last = if latin1 { 'ÿ' } else { '\u{a0}' };
+    for c in (' '..='~').chain('¡'..=last) {
+        let mut pixels = vec![false; (width * height) as usize];
+        for (x, y) in render(&c.to_string()) {
+            if x < width && y < height {
+                pixels[(y * width + x) as usize] = true;
+            }
+        }
+        font.insert(c, Glyph::new(width, height, width, pixels));
+    }
+    font
+}
@@ -0,0 +1,320 @@
+// 8x8 icons for common Slack emoji shortcodes, one bit per pixel like `Bitmap`
+
+const COFFEE: &[u8] = &[
+    0b00101000,
+    0b01010000,
+    0b00000000,
+    0b11111100,
+    0b10000111,
+    0b10000101,
+    0b01111010,
+    0b00110000,
+];
+const TEA: &[u8] = &[
+    0b00000000,
+    0b00010000,
+    0b00111000,
+    0b01111100,
+    0b11111110,
+    0b11111110,
+    0b01111100,
+    0b00111000,
+];
+const SMILE: &[u8] = &[
+    0b00111100,
+    0b01000010,
+    0b10100101,
+    0b10000001,
+    0b10100101,
+    0b10011001,
+    0b01000010,
+    0b00111100,
+];
+const NEUTRAL_FACE: &[u8] = &[
+    0b00111100,
+    0b01000010,
+    0b10100101,
+    0b10000001,
+    0b10000001,
+    0b10111101,
+    0b01000010,
+    0b00111100,
+];
+const HOUSE: &[u8] = &[
+    0b00011000,
+    0b00111100,
+    0b01111110,
+    0b11111111,
+    0b01000010,
+    0b01011010,
+    0b01011010,
+    0b01111110,
+];
+const OFFICE: &[u8] = &[
+    0b11111111,
+    0b10101011,
+    0b11111111,
+    0b10101011,
+    0b11111111,
+    0b10101011,
+    0b11100111,
+    0b11100111,
+];
+const CALENDAR: &[u8] = &[
+    0b01000010,
+    0b11111111,
+    0b10000001,
+    0b11111111,
+    0b10101011,
+    0b10000001,
+    0b10101011,
+    0b11111111,
+];
+const CAR: &[u8] = &[
+    0b00000000,
+    0b00111100,
+    0b01010010,
+    0b11111111,
+    0b10111101,
+    0b11111111,
+    0b01100110,
+    0b00000000,
+];
+const TRAIN: &[u8] = &[
+    0b01111110,
+    0b10000001,
+    0b10100101,
+    0b10000001,
+    0b11111111,
+    0b10100101,
+    0b11111111,
+    0b01000010,
+];
+const AIRPLANE: &[u8] = &[
+    0b00011000,
+    0b00011000,
+    0b01111110,
+    0b11111111,
+    0b00011000,
+    0b00011000,
+    0b00111100,
+    0b00000000,
+];
+const PALM_TREE: &[u8] = &[
+    0b01110110,
+    0b11011101,
+    0b10010010,
+    0b00010000,
+    0b00010000,
+    0b00001000,
+    0b00001000,
+    0b00111100,
+];
+const HAMBURGER: &[u8] = &[
+    0b00111100,
+    0b01010010,
+    0b11111111,
+    0b00000000,
+    0b11111111,
+    0b00000000,
+    0b11111111,
+    0b01111110,
+];
+const HEART: &[u8] = &[
+    0b00000000,
+    0b01100110,
+    0b11111111,
+    0b11111111,
+    0b01111110,
+    0b00111100,
+    0b00011000,
+    0b00000000,
+];
+const THUMBSUP: &[u8] = &[
+    0b00010000,
+    0b00110000,
+    0b00110000,
+    0b10111111,
+    0b10100001,
+    0b10111111,
+    0b10100001,
+    0b10111110,
+];
+const SUNNY: &[u8] = &[
+    0b10010010,
+    0b01000100,
+    0b00011000,
+    0b10111101,
+    0b00111100,
+    0b00011000,
+    0b01000100,
+    0b10010010,
+];
+const CLOUD: &[u8] = &[
+    0b00000000,
+    0b00011000,
+    0b00100100,
+    0b01000011,
+    0b10000000,
+    0b10000001,
+    0b01111110,
+    0b00000000,
+];
+const UMBRELLA: &[u8] = &[
+    0b00011000,
+    0b01111110,
+    0b11111111,
+    0b00010000,
+    0b00010000,
+    0b00010000,
+    0b01010000,
+    0b00100000,
+];
+const ZZZ: &[u8] = &[
+    0b00001111,
+    0b00000010,
+    0b11100100,
+    0b00101111,
+    0b01000000,
+    0b11100000,
+    0b00000000,
+    0b00000000,
+];
+const COMPUTER: &[u8] = &[
+    0b11111111,
+    0b10000001,
+    0b10000001,
+    0b10000001,
+    0b11111111,
+    0b00011000,
+    0b01111110,
+    0b00000000,
+];
+const PHONE: &[u8] = &[
+    0b01111000,
+    0b01001000,
+    0b01111000,
+    0b01111000,
+    0b01111000,
+    0b01111000,
+    0b01011000,
+    0b01111000,
+];
+const HEADPHONES: &[u8] = &[
+    0b00111100,
+    0b01000010,
+    0b10000001,
+    0b10000001,
+    0b11000011,
+    0b11100111,
+    0b11100111,
+    0b01000010,
+];
+const SPEECH_BALLOON: &[u8] = &[
+    0b01111110,
+    0b10000001,
+    0b10101001,
+    0b10000001,
+    0b01111110,
+    0b00100000,
+    0b01000000,
+    0b00000000,
+];
+const NO_ENTRY: &[u8] = &[
+    0b00111100,
+    0b01111110,
+    0b11111111,
+    0b10000001,
+    0b10000001,
+    0b11111111,
+    0b01111110,
+    0b00111100,
+];
+const WHITE_CHECK_MARK: &[u8] = &[
+    0b00000000,
+    0b00000001,
+    0b00000011,
+    0b10000110,
+    0b11001100,
+    0b01111000,
+    0b00110000,
+    0b00000000,
+];
+const X: &[u8] = &[
+    0b11000011,
+    0b11100111,
+    0b01111110,
+    0b00111100,
+    0b00111100,
+    0b01111110,
+    0b11100111,
+    0b11000011,
+];
+const HOURGLASS: &[u8] = &[
+    0b11111111,
+    0b01000010,
+    0b00100100,
+    0b00011000,
+    0b00011000,
+    0b00100100,
+    0b01111110,
+    0b11111111,
+];
+const FACE_WITH_THERMOMETER: &[u8] = &[
+    0b00011000,
+    0b00100100,
+    0b00100100,
+    0b00100100,
+    0b00111100,
+    0b01111110,
+    0b01111110,
+    0b00111100,
+];
+const TADA: &[u8] = &[
+    0b00010000,
+    0b00101000,
+    0b11111111,
+    0b10010001,
+    0b11111111,
+    0b10010001,
+    0b10010001,
+    0b11111111,
+];
+
+const ICONS: &[(&[&str], &[u8])] = &[
+    (&["coffee"], COFFEE),
+    (&["tea"], TEA),
+    (&["smile", "slightly_smiling_face", "smiley", "blush"], SMILE),
+    (&["neutral_face", "expressionless"], NEUTRAL_FACE),
+    (&["house", "house_with_garden", "house_buildings"], HOUSE),
+    (&["office", "building_construction", "classical_building"], OFFICE),
+    (&["calendar", "spiral_calendar_pad", "date"], CALENDAR),
+    (&["car", "red_car", "oncoming_automobile", "taxi"], CAR),
+    (&["train", "train2", "bus", "tram"], TRAIN),
+    (&["airplane", "airplane_departure"], AIRPLANE),
+    (&["palm_tree", "desert_island", "beach_with_umbrella"], PALM_TREE),
+    (&["hamburger", "fork_and_knife", "knife_fork_plate", "pizza", "sandwich"], HAMBURGER),
+    (&["heart", "hearts", "red_heart"], HEART),
+    (&["thumbsup", "+1", "thumbs_up"], THUMBSUP),
+    (&["sunny", "sun_with_face", "sunrise"], SUNNY),
+    (&["cloud", "partly_sunny", "fog"], CLOUD),
+    (&["umbrella", "rain_cloud", "umbrella_with_rain_drops"], UMBRELLA),
+    (&["zzz", "sleeping", "sleeping_accommodation", "bed"], ZZZ),
+    (&["computer", "desktop_computer", "technologist"], COMPUTER),
+    (&["phone", "telephone_receiver", "iphone", "calling"], PHONE),
+    (&["headphones", "headphone"], HEADPHONES),
+    (&["speech_balloon", "thought_balloon", "left_speech_bubble"], SPEECH_BALLOON),
+    (&["no_entry", "no_entry_sign", "red_circle", "octagonal_sign"], NO_ENTRY),
+    (&["white_check_mark", "heavy_check_mark", "ballot_box_with_check"], WHITE_CHECK_MARK),
+    (&["x", "heavy_multiplication_x", "negative_squared_cross_mark"], X),
+    (&["hourglass", "hourglass_flowing_sand", "stopwatch", "clock"], HOURGLASS),
+    (&["face_with_thermometer", "thermometer", "pill", "mask"], FACE_WITH_THERMOMETER),
+    (&["tada", "birthday", "cake", "gift"], TADA),
+];
+
+/// Look up the icon for an emoji shortcode, given without its surrounding colons
+pub fn emoji_icon(name: &str) -> Option<&'static [u8]> {
+    ICONS.iter()
+        .find(|(names, _)| names.contains(&name))
+        .map(|(_, icon)| *icon)
+}
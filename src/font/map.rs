@@ -0,0 +1,91 @@
+use std::rc::Rc;
+
+use crate::font::{emoji_icon, BitmapFont, Glyph};
+
+// accented Latin letters and the unaccented letters they fall back to
+const FOLD_FROM: &str = "ÀÁÂÃÄÅÇÈÉÊËÌÍÎÏÑÒÓÔÕÖÙÚÛÜÝàáâãäåçèéêëìíîïñòóôõöùúûüýÿĀāĂăĄąĆćĈĉĊċČčĎďĐđĒēĔĕĖėĘęĚěĜĝĞğĠġĢģĤĥĦħĨĩĪīĬĭĮįİıĴĵĶķĹĺĻļĽľŁłŃńŅņŇňŌōŎŏŐőŔŕŖŗŘřŚśŜŝŞşŠšŢţŤťŦŧŨũŪūŬŭŮůŰűŲųŴŵŶŷŸŹźŻżŽž";
+const FOLD_TO: &str = "AAAAAACEEEEIIIINOOOOOUUUUYaaaaaaceeeeiiiinooooouuuuyyAaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIiJjKkLlLlLlLlNnNnNnOoOoOoRrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZz";
+
+// characters with no single-letter fallback, spelt out instead
+const TRANSLITERATIONS: &[(char, &str)] = &[
+    ('Æ', "AE"), ('æ', "ae"), ('Œ', "OE"), ('œ', "oe"), ('Ĳ', "IJ"), ('ĳ', "ij"),
+    ('ß', "ss"), ('Þ', "Th"), ('þ', "th"), ('Ð', "D"), ('ð', "d"), ('Ø', "O"), ('ø', "o"),
+    ('Ŀ', "L."), ('ŀ', "l."), ('ŉ', "'n"), ('Ŋ', "N"), ('ŋ', "n"), ('ĸ', "k"), ('ſ', "s"),
+    ('‘', "'"), ('’', "'"), ('‚', ","), ('“', "\""), ('”', "\""), ('„', "\""),
+    ('–', "-"), ('—', "-"), ('…', "..."), ('•', "*"), ('·', "."), ('«', "<<"), ('»', ">>"), ('‹', "<"), ('›', ">"),
+    ('€', "EUR"), ('™', "TM"), ('\u{a0}', " "), ('\t', " "),
+];
+
+// longest emoji shortcode name that will be looked up
+const MAX_SHORTCODE: usize = 32;
+
+/// A unit of laid out text: one character, or one emoji shortcode, along with
+/// the glyphs it is drawn with.
+#[derive(Clone)]
+pub struct Token {
+    pub glyphs: Vec<Rc<Glyph>>,
+    pub space: bool,
+}
+
+impl Token {
+    pub fn width(&self) -> i32 {
+        self.glyphs.iter().map(|glyph| glyph.advance).sum()
+    }
+}
+
+/// Map each character in `text` to glyphs in `font`. Characters the font lacks
+/// fall back to their unaccented letter, then to a transliteration, and finally
+/// to a replacement box. With `emoji` set, known shortcodes like `:coffee:` are
+/// drawn as icons.
+pub fn tokenize(font: &BitmapFont, text: &str, emoji: bool) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if emoji && c == ':' {
+            if let Some((icon, length)) = shortcode(rest) {
+                tokens.push(Token { glyphs: vec![Rc::new(icon_glyph(font, icon))], space: false });
+                rest = &rest[length..];
+                continue;
+            }
+        }
+        tokens.push(Token { glyphs: map_char(font, c), space: c == ' ' });
+        rest = &rest[c.len_utf8()..];
+    }
+    tokens
+}
+
+fn map_char(font: &BitmapFont, c: char) -> Vec<Rc<Glyph>> {
+    if let Some(glyph) = font.shared_glyph(c) {
+        return vec![glyph];
+    }
+    if let Some(i) = FOLD_FROM.chars().position(|from| from == c) {
+        let base = FOLD_TO.chars().nth(i).unwrap();
+        if let Some(glyph) = font.shared_glyph(base) {
+            return vec![glyph];
+        }
+    }
+    if let Some((_, spelling)) = TRANSLITERATIONS.iter().find(|(from, _)| *from == c) {
+        let glyphs: Vec<Rc<Glyph>> = spelling.chars().filter_map(|c| font.shared_glyph(c)).collect();
+        if glyphs.len() == spelling.chars().count() {
+            return glyphs;
+        }
+    }
+    vec![Rc::new(font.replacement())]
+}
+
+/// Find a known `:shortcode:` at the start of `text`, returning its icon and
+/// length in bytes.
+fn shortcode(text: &str) -> Option<(&'static [u8], usize)> {
+    let end = text[1..].find(':')? + 1;
+    let name = &text[1..end];
+    if name.is_empty() || name.len() > MAX_SHORTCODE {
+        return None;
+    }
+    emoji_icon(name).map(|icon| (icon, end + 1))
+}
+
+/// An 8x8 icon as a glyph, centred vertically on the line
+fn icon_glyph(font: &BitmapFont, icon: &[u8]) -> Glyph {
+    let pixels = (0..64).map(|i| icon[i / 8] & (0x80 >> (i % 8)) != 0).collect();
+    Glyph::new(8, 8, 9, pixels).offset(0, (font.line_height() - 8) / 2)
+}
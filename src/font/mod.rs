@@ -0,0 +1,166 @@
+mod bdf;
+mod builtin;
+mod emoji;
+mod map;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+pub use bdf::FontError;
+pub use emoji::emoji_icon;
+pub use map::{tokenize, Token};
+
+/// A single glyph of a `BitmapFont`
+pub struct Glyph {
+    pub width: i32,
+    pub height: i32,
+    /// Offset of the glyph's left edge from the pen position
+    pub x_offset: i32,
+    /// Offset of the glyph's top edge from the top of the line
+    pub y_offset: i32,
+    /// How far the pen moves after drawing this glyph
+    pub advance: i32,
+    pixels: Vec<bool>,
+}
+
+impl Glyph {
+    pub fn new(width: i32, height: i32, advance: i32, pixels: Vec<bool>) -> Glyph {
+        Glyph {
+            width, height,
+            x_offset: 0, y_offset: 0,
+            advance,
+            pixels
+        }
+    }
+
+    pub fn offset(mut self, x: i32, y: i32) -> Glyph { self.x_offset = x; self.y_offset = y; self }
+
+    /// Whether the pixel at (x, y) inside the glyph's bounding box is set
+    pub fn is_set(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
+
+/// A set of glyphs sharing a line height, either built in or loaded from a BDF file
+pub struct BitmapFont {
+    glyphs: HashMap<char, Rc<Glyph>>,
+    ascent: i32,
+    descent: i32,
+    default_char: char,
+}
+
+impl BitmapFont {
+    pub fn new(ascent: i32, descent: i32) -> BitmapFont {
+        BitmapFont {
+            glyphs: HashMap::new(),
+            ascent, descent,
+            default_char: '?'
+        }
+    }
+
+    /// Parse a font from the contents of a BDF file
+    pub fn from_bdf(source: &str) -> Result<BitmapFont, FontError> {
+        bdf::parse(source)
+    }
+
+    /// Load a font from a BDF file on disk
+    pub fn load_bdf<P: AsRef<Path>>(path: P) -> Result<BitmapFont, FontError> {
+        let source = std::fs::read_to_string(path)?;
+        bdf::parse(&source)
+    }
+
+    pub fn insert(&mut self, c: char, glyph: Glyph) {
+        self.glyphs.insert(c, Rc::new(glyph));
+    }
+
+    /// Add the glyphs from `other` that this font doesn't already have
+    pub fn extend(&mut self, other: BitmapFont) {
+        for (c, glyph) in other.glyphs {
+            self.glyphs.entry(c).or_insert(glyph);
+        }
+    }
+
+    pub fn set_default_char(&mut self, c: char) { self.default_char = c; }
+
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).map(|glyph| &**glyph)
+    }
+
+    /// Get the glyph for `c`, falling back to the font's default character
+    pub fn glyph_or_default(&self, c: char) -> Option<&Glyph> {
+        self.glyph(c).or_else(|| self.glyph(self.default_char))
+    }
+
+    fn shared_glyph(&self, c: char) -> Option<Rc<Glyph>> {
+        self.glyphs.get(&c).cloned()
+    }
+
+    /// A hollow box the size of the default character, drawn for characters
+    /// the font can't show
+    pub fn replacement(&self) -> Glyph {
+        let (width, advance) = match self.glyph(self.default_char) {
+            Some(glyph) => (glyph.advance - 1, glyph.advance),
+            None => (self.line_height() / 2, self.line_height() / 2 + 1),
+        };
+        let height = self.ascent - 1;
+        let pixels = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                x == 0 || y == 0 || x == width - 1 || y == height - 1
+            })
+            .collect();
+        Glyph::new(width, height, advance, pixels).offset(0, 1)
+    }
+
+    /// Width in pixels of a single line of text
+    pub fn text_width(&self, text: &str) -> i32 {
+        text.chars()
+            .filter_map(|c| self.glyph_or_default(c))
+            .map(|glyph| glyph.advance)
+            .sum()
+    }
+
+    /// The string used to mark truncated text, using a real ellipsis when the font has one
+    pub fn ellipsis(&self) -> &'static str {
+        if self.glyphs.contains_key(&'…') { "…" } else { "..." }
+    }
+
+    pub fn ascent(&self) -> i32 { self.ascent }
+    pub fn descent(&self) -> i32 { self.descent }
+    pub fn line_height(&self) -> i32 { self.ascent + self.descent }
+}
+
+/// The fonts `shape::Text` can be drawn with
+#[derive(Clone, Default)]
+pub enum Font {
+    Tiny4x6,
+    #[default]
+    Font6x8,
+    Font6x12,
+    Font8x16,
+    BigDigits,
+    Custom(Rc<BitmapFont>),
+}
+
+impl Font {
+    /// Load a custom font from a BDF file on disk
+    pub fn load_bdf<P: AsRef<Path>>(path: P) -> Result<Font, FontError> {
+        Ok(Font::Custom(Rc::new(BitmapFont::load_bdf(path)?)))
+    }
+
+    /// Get the glyph table backing this font. Built-in fonts are only built once.
+    pub fn bitmap(&self) -> Rc<BitmapFont> {
+        match self {
+            Font::Tiny4x6 => builtin::TINY_4X6.with(|font| font.clone()),
+            Font::Font6x8 => builtin::FONT_6X8.with(|font| font.clone()),
+            Font::Font6x12 => builtin::FONT_6X12.with(|font| font.clone()),
+            Font::Font8x16 => builtin::FONT_8X16.with(|font| font.clone()),
+            Font::BigDigits => builtin::BIG_DIGITS.with(|font| font.clone()),
+            Font::Custom(font) => font.clone(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::view::{View, ViewSpawner};
+
+const DEFAULT_CONTRAST: u8 = 0x8F;
+const DEFAULT_DIM_CONTRAST: u8 = 0x01;
+
+enum State {
+    Awake,
+    Dimmed,
+    /// Past `blank_after`, showing the screen saver or with the panel switched off
+    Asleep(Option<Box<dyn View>>),
+    /// Woken by a button that is still down, so its presses are ignored until released
+    Waking,
+}
+
+/// Dims and then blanks the display when no buttons have been pressed for a while,
+/// to save the OLED from burn-in. Disabled until `dim_after` or `blank_after` is set.
+pub struct Idle {
+    dim_after: Option<Duration>,
+    blank_after: Option<Duration>,
+    contrast: u8,
+    dim_contrast: u8,
+    screensaver: Option<Box<dyn ViewSpawner>>,
+    last_input: Instant,
+    state: State,
+}
+
+impl Default for Idle { fn default() -> Idle { Idle::new() } }
+
+impl Idle {
+    pub fn new() -> Idle {
+        Idle {
+            dim_after: None,
+            blank_after: None,
+            contrast: DEFAULT_CONTRAST,
+            dim_contrast: DEFAULT_DIM_CONTRAST,
+            screensaver: None,
+            last_input: Instant::now(),
+            state: State::Awake,
+        }
+    }
+
+    pub fn dim_after(mut self, timeout: Duration) -> Idle { self.dim_after = Some(timeout); self }
+    pub fn blank_after(mut self, timeout: Duration) -> Idle { self.blank_after = Some(timeout); self }
+    /// Contrast to use while awake and while dimmed
+    pub fn contrast(mut self, awake: u8, dimmed: u8) -> Idle {
+        self.contrast = awake;
+        self.dim_contrast = dimmed;
+        self
+    }
+    /// Run this view, dimmed, instead of switching the panel off once blanked
+    pub fn screensaver(mut self, spawner: Box<dyn ViewSpawner>) -> Idle {
+        self.screensaver = Some(spawner);
+        self
+    }
+
+    /// Whether the views should be shown this frame
+    pub fn is_awake(&self) -> bool {
+        match self.state {
+            State::Awake | State::Dimmed | State::Waking => true,
+            State::Asleep(_) => false,
+        }
+    }
+
+    /// Track button input and move between states. A press while dimmed or asleep
+    /// only wakes the display; it is consumed rather than passed on to the views.
+    pub fn update(&mut self, buttons: &mut ButtonSet, screen: &mut Screen) {
+        if let State::Waking = self.state {
+            if buttons.any_down() {
+                buttons.flush();
+                self.last_input = Instant::now();
+                return;
+            }
+            self.state = State::Awake;
+        }
+
+        if buttons.any_pressed() {
+            self.last_input = Instant::now();
+            match self.state {
+                State::Awake | State::Waking => {}
+                State::Dimmed => {
+                    screen.set_contrast(self.contrast);
+                    buttons.flush();
+                    self.state = State::Waking;
+                }
+                State::Asleep(ref saver) => {
+                    if saver.is_none() {
+                        screen.set_display_on(true);
+                    }
+                    screen.set_contrast(self.contrast);
+                    buttons.flush();
+                    self.state = State::Waking;
+                }
+            }
+            return;
+        }
+
+        let idle = self.last_input.elapsed();
+        match self.state {
+            State::Awake if self.dim_after.is_some_and(|timeout| idle >= timeout) => {
+                screen.set_contrast(self.dim_contrast);
+                self.state = State::Dimmed;
+            }
+            State::Awake | State::Dimmed if self.blank_after.is_some_and(|timeout| idle >= timeout) => {
+                let saver = self.screensaver.as_ref().map(|spawner| spawner.spawn());
+                match saver {
+                    Some(_) => screen.set_contrast(self.dim_contrast),
+                    None => screen.set_display_on(false),
+                }
+                self.state = State::Asleep(saver);
+            }
+            State::Asleep(Some(ref mut saver)) => {
+                saver.update(buttons);
+            }
+            _ => {}
+        }
+    }
+
+    /// Draw the screen saver, if one is running
+    pub fn render(&self, screen: &mut Screen) {
+        if let State::Asleep(Some(ref saver)) = self.state {
+            saver.render(screen);
+        }
+    }
+}
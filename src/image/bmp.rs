@@ -0,0 +1,125 @@
+use std::convert::TryFrom;
+
+use crate::image::ImageError;
+use crate::shape::Bitmap;
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_i32(bytes: &[u8], at: usize) -> i32 {
+    i32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Decode an uncompressed 1-bit BMP. Pixels using the darker of the two palette
+/// colours are lit.
+pub fn decode(bytes: &[u8]) -> Result<Bitmap, ImageError> {
+    if bytes.len() < 54 {
+        return Err(ImageError::Format("truncated BMP header".to_owned()));
+    }
+    let data_offset = read_i32(bytes, 10);
+    let header_size = read_i32(bytes, 14);
+    let width = read_i32(bytes, 18);
+    let raw_height = read_i32(bytes, 22);
+    let bits_per_pixel = read_u16(bytes, 28);
+    let compression = read_i32(bytes, 30);
+
+    if bits_per_pixel != 1 {
+        return Err(ImageError::Unsupported(format!("{}-bit BMP", bits_per_pixel)));
+    }
+    if compression != 0 {
+        return Err(ImageError::Unsupported("compressed BMP".to_owned()));
+    }
+    if data_offset < 0 || header_size < 0 {
+        return Err(ImageError::Format("negative BMP offset".to_owned()));
+    }
+    if width <= 0 || raw_height == 0 {
+        return Err(ImageError::Format("invalid BMP dimensions".to_owned()));
+    }
+    let too_large = || ImageError::Format("BMP too large".to_owned());
+    let data_offset = data_offset as usize;
+
+    // the palette follows the header, as blue, green, red and padding bytes
+    let palette = (header_size as usize).checked_add(14).ok_or_else(too_large)?;
+    if bytes.len() < palette.checked_add(8).ok_or_else(too_large)? {
+        return Err(ImageError::Format("truncated BMP palette".to_owned()));
+    }
+    let brightness = |i: usize| -> u32 {
+        bytes[palette + i * 4..palette + i * 4 + 3].iter().map(|&c| c as u32).sum()
+    };
+    let lit_index = if brightness(0) <= brightness(1) { 0 } else { 1 };
+
+    // rows are stored bottom-up unless the height is negative, and padded to 4 bytes
+    let height = i32::try_from(raw_height.unsigned_abs()).map_err(|_| too_large())?;
+    let (columns, rows) = (width as usize, height as usize);
+    let stride = columns.div_ceil(32) * 4;
+    let end = stride.checked_mul(rows)
+        .and_then(|length| length.checked_add(data_offset))
+        .ok_or_else(too_large)?;
+    if bytes.len() < end {
+        return Err(ImageError::Format("truncated BMP data".to_owned()));
+    }
+    let mut pixels = Vec::with_capacity(columns.checked_mul(rows).ok_or_else(too_large)?);
+    for y in 0..rows {
+        let row = if raw_height > 0 { rows - 1 - y } else { y };
+        let start = data_offset + row * stride;
+        for x in 0..columns {
+            let bit = (bytes[start + x / 8] >> (7 - x % 8)) & 1;
+            pixels.push(bit == lit_index);
+        }
+    }
+    Ok(Bitmap::from_pixels(&pixels, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1-bit BMP with a black and white palette and `data` after it
+    fn bmp(width: i32, height: i32, data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0; 62];
+        bytes[0..2].copy_from_slice(b"BM");
+        bytes[10..14].copy_from_slice(&62i32.to_le_bytes());
+        bytes[14..18].copy_from_slice(&40i32.to_le_bytes());
+        bytes[18..22].copy_from_slice(&width.to_le_bytes());
+        bytes[22..26].copy_from_slice(&height.to_le_bytes());
+        bytes[28..30].copy_from_slice(&1u16.to_le_bytes());
+        bytes[58..61].copy_from_slice(&[255, 255, 255]);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn is_format_error(result: Result<Bitmap, ImageError>) -> bool {
+        matches!(result, Err(ImageError::Format(_)))
+    }
+
+    #[test]
+    fn decodes_bottom_up_rows() {
+        // palette entry 0 is black, so clear bits are lit
+        let image = decode(&bmp(2, 2, &[0x40, 0, 0, 0, 0x80, 0, 0, 0])).expect("valid BMP");
+        assert!(!image.get_pixel(0, 0) && image.get_pixel(1, 0));
+        assert!(image.get_pixel(0, 1) && !image.get_pixel(1, 1));
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        assert!(is_format_error(decode(&bmp(2, 2, &[])[..40])));
+        assert!(is_format_error(decode(&bmp(2, 2, &[0, 0, 0, 0]))));
+    }
+
+    #[test]
+    fn rejects_hostile_headers() {
+        assert!(is_format_error(decode(&bmp(-8, 2, &[0; 8]))));
+        assert!(is_format_error(decode(&bmp(0, 2, &[0; 8]))));
+        assert!(is_format_error(decode(&bmp(8, 0, &[0; 8]))));
+        assert!(is_format_error(decode(&bmp(8, i32::MIN, &[0; 8]))));
+        assert!(is_format_error(decode(&bmp(i32::MAX, i32::MAX, &[0; 8]))));
+
+        let mut offset = bmp(8, 1, &[0; 4]);
+        offset[10..14].copy_from_slice(&(-1i32).to_le_bytes());
+        assert!(is_format_error(decode(&offset)));
+        let mut header = bmp(8, 1, &[0; 4]);
+        header[14..18].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(is_format_error(decode(&header)));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::image::GreyImage;
+
+const BAYER_4X4: [[i32; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// How greyscale images are reduced to one bit per pixel
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dither {
+    /// Light pixels at or above the given level, with no dithering
+    Threshold(u8),
+    /// Floyd–Steinberg error diffusion, best for photos
+    FloydSteinberg,
+    /// 4x4 Bayer matrix, which gives a regular pattern that holds still in animations
+    Ordered,
+}
+
+impl Default for Dither {
+    fn default() -> Dither { Dither::Threshold(128) }
+}
+
+impl Dither {
+    /// Decide which pixels of an image are lit
+    pub fn apply(&self, image: &GreyImage) -> Vec<bool> {
+        let (width, height) = (image.width, image.height);
+        match *self {
+            Dither::Threshold(level) => image.pixels.iter().map(|&p| p >= level).collect(),
+            Dither::Ordered => image.pixels.iter()
+                .enumerate()
+                .map(|(i, &p)| {
+                    let threshold = BAYER_4X4[(i / width) % 4][(i % width) % 4] * 16 + 8;
+                    p as i32 >= threshold
+                })
+                .collect(),
+            Dither::FloydSteinberg => {
+                let mut levels: Vec<i32> = image.pixels.iter().map(|&p| p as i32).collect();
+                let mut lit = vec![false; levels.len()];
+                for y in 0..height {
+                    for x in 0..width {
+                        let i = y * width + x;
+                        lit[i] = levels[i] >= 128;
+                        let error = levels[i] - if lit[i] { 255 } else { 0 };
+                        let mut spread = |dx: isize, dy: usize, weight: i32| {
+                            let nx = x as isize + dx;
+                            if nx >= 0 && (nx as usize) < width && y + dy < height {
+                                levels[(y + dy) * width + nx as usize] += error * weight / 16;
+                            }
+                        };
+                        spread(1, 0, 7);
+                        spread(-1, 1, 3);
+                        spread(0, 1, 5);
+                        spread(1, 1, 1);
+                    }
+                }
+                lit
+            }
+        }
+    }
+}
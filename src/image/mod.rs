@@ -0,0 +1,138 @@
+mod bmp;
+mod dither;
+mod pbm;
+
+use std::fmt;
+use std::io::{self, Cursor};
+use std::path::Path;
+
+use crate::shape::Bitmap;
+
+pub use dither::Dither;
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    Format(String),
+    Unsupported(String),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageError::Io(err) => write!(f, "could not read image: {}", err),
+            ImageError::Format(msg) => write!(f, "invalid image: {}", msg),
+            ImageError::Unsupported(msg) => write!(f, "unsupported image: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<io::Error> for ImageError {
+    fn from(err: io::Error) -> ImageError { ImageError::Io(err) }
+}
+
+/// An 8-bit greyscale image, where 255 is a fully lit pixel
+pub struct GreyImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl GreyImage {
+    /// Reduce to one bit per pixel, packed into rows the way `Bitmap` expects
+    pub fn to_bitmap(&self, dither: Dither) -> Bitmap {
+        let lit = dither.apply(self);
+        Bitmap::from_pixels(&lit, self.width as i32, self.height as i32)
+    }
+}
+
+/// Decode a PBM, 1-bit BMP, PNG or JPEG image, working out the format from its contents.
+///
+/// PBM and BMP images are already one bit per pixel, so `dither` is ignored and
+/// their black pixels are lit. PNG and JPEG images are converted to greyscale
+/// and their bright pixels are lit; transparent pixels are left unlit.
+pub fn decode(bytes: &[u8], dither: Dither) -> Result<Bitmap, ImageError> {
+    if bytes.starts_with(b"P1") || bytes.starts_with(b"P4") {
+        pbm::decode(bytes)
+    } else if bytes.starts_with(b"BM") {
+        bmp::decode(bytes)
+    } else if bytes.starts_with(b"\x89PNG") {
+        Ok(decode_png(bytes)?.to_bitmap(dither))
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        Ok(decode_jpeg(bytes)?.to_bitmap(dither))
+    } else {
+        Err(ImageError::Unsupported("unrecognised file format".to_owned()))
+    }
+}
+
+/// Read and decode an image file from disk
+pub fn load<P: AsRef<Path>>(path: P, dither: Dither) -> Result<Bitmap, ImageError> {
+    decode(&std::fs::read(path)?, dither)
+}
+
+/// Average the colour channels of each pixel, blending any alpha channel over black
+fn to_grey(data: &[u8], channels: usize, alpha: bool) -> Vec<u8> {
+    data.chunks(channels)
+        .map(|pixel| {
+            let colours = if alpha { channels - 1 } else { channels };
+            let grey = pixel[..colours].iter().map(|&c| c as u32).sum::<u32>() / colours as u32;
+            match alpha {
+                true => (grey * pixel[channels - 1] as u32 / 255) as u8,
+                false => grey as u8,
+            }
+        })
+        .collect()
+}
+
+pub fn decode_png(bytes: &[u8]) -> Result<GreyImage, ImageError> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()
+        .map_err(|err| ImageError::Format(err.to_string()))?;
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data)
+        .map_err(|err| ImageError::Format(err.to_string()))?;
+    data.truncate(info.buffer_size());
+
+    let (channels, alpha) = match info.color_type {
+        png::ColorType::Grayscale => (1, false),
+        png::ColorType::GrayscaleAlpha => (2, true),
+        png::ColorType::Rgb => (3, false),
+        png::ColorType::Rgba => (4, true),
+        png::ColorType::Indexed => {
+            return Err(ImageError::Unsupported("unexpanded palette".to_owned()))
+        }
+    };
+    Ok(GreyImage {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels: to_grey(&data, channels, alpha),
+    })
+}
+
+pub fn decode_jpeg(bytes: &[u8]) -> Result<GreyImage, ImageError> {
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(bytes));
+    let data = decoder.decode()
+        .map_err(|err| ImageError::Format(err.to_string()))?;
+    let info = decoder.info()
+        .ok_or_else(|| ImageError::Format("missing JPEG header".to_owned()))?;
+
+    let pixels = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => data,
+        jpeg_decoder::PixelFormat::L16 => data.chunks(2).map(|pixel| pixel[0]).collect(),
+        jpeg_decoder::PixelFormat::RGB24 => to_grey(&data, 3, false),
+        jpeg_decoder::PixelFormat::CMYK32 => data.chunks(4)
+            .map(|pixel| {
+                let ink = pixel[..3].iter().map(|&c| c as u32).sum::<u32>() / 3;
+                ((255 - ink) * (255 - pixel[3] as u32) / 255) as u8
+            })
+            .collect(),
+    };
+    Ok(GreyImage {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    })
+}
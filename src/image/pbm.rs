@@ -0,0 +1,107 @@
+use std::convert::TryFrom;
+
+use crate::image::ImageError;
+use crate::shape::Bitmap;
+
+/// Split off the next whitespace separated header field, skipping `#` comments
+fn field<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<&'a [u8], ImageError> {
+    loop {
+        while *pos < bytes.len() && bytes[*pos].is_ascii_whitespace() {
+            *pos += 1;
+        }
+        if *pos < bytes.len() && bytes[*pos] == b'#' {
+            while *pos < bytes.len() && bytes[*pos] != b'\n' {
+                *pos += 1;
+            }
+        } else {
+            break;
+        }
+    }
+    let start = *pos;
+    while *pos < bytes.len() && !bytes[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    match start == *pos {
+        true => Err(ImageError::Format("truncated PBM header".to_owned())),
+        false => Ok(&bytes[start..*pos]),
+    }
+}
+
+fn dimension(bytes: &[u8], pos: &mut usize) -> Result<usize, ImageError> {
+    let field = field(bytes, pos)?;
+    std::str::from_utf8(field)
+        .ok()
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| ImageError::Format("invalid PBM dimensions".to_owned()))
+}
+
+/// Decode a plain (P1) or raw (P4) PBM image. Black pixels are lit.
+pub fn decode(bytes: &[u8]) -> Result<Bitmap, ImageError> {
+    let mut pos = 0;
+    let magic = field(bytes, &mut pos)?;
+    let width = dimension(bytes, &mut pos)?;
+    let height = dimension(bytes, &mut pos)?;
+    let too_large = || ImageError::Format("PBM too large".to_owned());
+    let pixel_count = width.checked_mul(height).ok_or_else(too_large)?;
+    let (columns, rows) = match (i32::try_from(width), i32::try_from(height)) {
+        (Ok(columns), Ok(rows)) => (columns, rows),
+        _ => return Err(too_large()),
+    };
+
+    if magic == b"P4" {
+        // a single whitespace character separates the header from the packed rows,
+        // which are already laid out the way Bitmap wants them
+        let start = pos + 1;
+        let length = width.div_ceil(8).checked_mul(height).ok_or_else(too_large)?;
+        let end = start.checked_add(length).ok_or_else(too_large)?;
+        if bytes.len() < end {
+            return Err(ImageError::Format("truncated PBM data".to_owned()));
+        }
+        return Ok(Bitmap::new(&bytes[start..end], columns, rows));
+    }
+
+    let pixels: Vec<bool> = bytes[pos..].iter()
+        .filter(|&&b| b == b'0' || b == b'1')
+        .map(|&b| b == b'1')
+        .take(pixel_count)
+        .collect();
+    if pixels.len() < pixel_count {
+        return Err(ImageError::Format("truncated PBM data".to_owned()));
+    }
+    Ok(Bitmap::from_pixels(&pixels, columns, rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_format_error(result: Result<Bitmap, ImageError>) -> bool {
+        matches!(result, Err(ImageError::Format(_)))
+    }
+
+    #[test]
+    fn decodes_plain_and_raw() {
+        let plain = decode(b"P1\n# comment\n3 2\n1 0 1\n0 1 0\n").expect("valid P1");
+        assert!(plain.get_pixel(0, 0) && !plain.get_pixel(1, 0) && plain.get_pixel(1, 1));
+        let raw = decode(b"P4\n3 2\n\xa0\x40").expect("valid P4");
+        assert!(raw.get_pixel(0, 0) && !raw.get_pixel(1, 0) && raw.get_pixel(1, 1));
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        assert!(is_format_error(decode(b"P1\n3")));
+        assert!(is_format_error(decode(b"P1\n3 2\n1 0 1\n")));
+        assert!(is_format_error(decode(b"P4\n16 2\n\xff\xff")));
+    }
+
+    #[test]
+    fn rejects_hostile_headers() {
+        let huge = format!("P4\n{} {}\n", usize::MAX, usize::MAX);
+        assert!(is_format_error(decode(huge.as_bytes())));
+        let plain = format!("P1\n{} 2\n", usize::MAX / 2 + 1);
+        assert!(is_format_error(decode(plain.as_bytes())));
+        let wide = format!("P4\n{} 1\n", i32::MAX as usize + 1);
+        assert!(is_format_error(decode(wide.as_bytes())));
+        assert!(is_format_error(decode(b"P4\n-3 2\n")));
+    }
+}
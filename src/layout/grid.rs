@@ -0,0 +1,140 @@
+use crate::layout::{share, Alignment, Size};
+use crate::screen::Screen;
+use crate::shape::Drawable;
+
+/// Cells in rows of a fixed number of columns, filled left to right then top to
+/// bottom. Every column is as wide as `column_width` makes it and every row as tall
+/// as `row_height`, and each cell is placed within its slot by the `Alignment`.
+pub struct Grid {
+    x: i32, y: i32,
+    columns: usize,
+    cells: Vec<Box<dyn Drawable>>,
+    column_width: Size,
+    row_height: Size,
+    padding: i32,
+    spacing: i32,
+    align: Alignment,
+}
+
+impl Grid {
+    pub fn new(columns: usize) -> Grid {
+        Grid {
+            x: 0, y: 0,
+            columns: columns.max(1),
+            cells: vec![],
+            column_width: Size::Fill,
+            row_height: Size::Fit,
+            padding: 0,
+            spacing: 0,
+            align: Alignment::Start,
+        }
+    }
+
+    pub fn push(&mut self, cell: Box<dyn Drawable>) {
+        self.cells.push(cell);
+    }
+
+    pub fn child(mut self, cell: Box<dyn Drawable>) -> Grid { self.push(cell); self }
+    pub fn at(mut self, x: i32, y: i32) -> Grid { self.x = x; self.y = y; self }
+    pub fn column_width(mut self, width: Size) -> Grid { self.column_width = width; self }
+    pub fn row_height(mut self, height: Size) -> Grid { self.row_height = height; self }
+    /// Space between the edge of the grid and its cells
+    pub fn padding(mut self, padding: i32) -> Grid { self.padding = padding; self }
+    /// Space between neighbouring cells, both across and down
+    pub fn spacing(mut self, spacing: i32) -> Grid { self.spacing = spacing; self }
+    /// Where each cell sits within its slot, both across and down
+    pub fn align(mut self, align: Alignment) -> Grid { self.align = align; self }
+
+    fn widths(&self, length: Option<i32>) -> Vec<i32> {
+        let fit = (0..self.columns)
+            .map(|column| self.cells.iter().skip(column).step_by(self.columns)
+                .map(|cell| cell.get_width())
+                .max()
+                .unwrap_or(0))
+            .collect();
+        self.tracks(self.column_width, fit, length)
+    }
+
+    fn heights(&self, length: Option<i32>) -> Vec<i32> {
+        let fit = self.cells.chunks(self.columns)
+            .map(|row| row.iter().map(|cell| cell.get_height()).max().unwrap_or(0))
+            .collect();
+        self.tracks(self.row_height, fit, length)
+    }
+
+    /// Sizes of each column or row, from their `Size` and what their cells need
+    fn tracks(&self, size: Size, fit: Vec<i32>, length: Option<i32>) -> Vec<i32> {
+        let count = fit.len();
+        match (size, length) {
+            (Size::Fixed(length), _) => vec![length; count],
+            (Size::Fill, Some(length)) => share(length - gaps(count, self.spacing), count).collect(),
+            (Size::Fit, _) | (Size::Fill, None) => fit,
+        }
+    }
+
+    fn fit(tracks: Vec<i32>, spacing: i32, padding: i32) -> i32 {
+        tracks.iter().sum::<i32>() + gaps(tracks.len(), spacing) + 2 * padding
+    }
+}
+
+fn gaps(count: usize, spacing: i32) -> i32 {
+    spacing * (count as i32 - 1).max(0)
+}
+
+impl Drawable for Grid {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        self.draw_in(x, y, self.get_width(), self.get_height(), screen)
+    }
+    fn draw_in(&self, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        let widths = self.widths(Some(width - 2 * self.padding));
+        let heights = self.heights(Some(height - 2 * self.padding));
+        let mut top = y + self.padding;
+        for (row, row_height) in self.cells.chunks(self.columns).zip(heights) {
+            let mut left = x + self.padding;
+            for (cell, &column_width) in row.iter().zip(&widths) {
+                let (dx, cell_width) = self.align.place(column_width, cell.get_width());
+                let (dy, cell_height) = self.align.place(row_height, cell.get_height());
+                cell.draw_in(left + dx, top + dy, cell_width, cell_height, screen);
+                left += column_width + self.spacing;
+            }
+            top += row_height + self.spacing;
+        }
+    }
+    fn get_x(&self) -> i32 { self.x }
+    fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { Grid::fit(self.widths(None), self.spacing, self.padding) }
+    fn get_height(&self) -> i32 { Grid::fit(self.heights(None), self.spacing, self.padding) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Space;
+
+    fn grid(columns: usize, cells: usize) -> Grid {
+        (0..cells).fold(Grid::new(columns), |grid, _| grid.child(Box::new(Space::new(10, 8))))
+    }
+
+    #[test]
+    fn fill_columns_split_the_width() {
+        assert_eq!(grid(3, 6).spacing(1).widths(Some(128)), [42, 42, 42]);
+    }
+
+    #[test]
+    fn last_column_takes_the_remainder() {
+        assert_eq!(grid(5, 5).widths(Some(128)), [25, 25, 25, 25, 28]);
+    }
+
+    #[test]
+    fn rows_fit_their_cells_unless_filled() {
+        assert_eq!(grid(2, 6).heights(Some(32)), [8, 8, 8]);
+        assert_eq!(grid(2, 6).row_height(Size::Fill).heights(Some(32)), [10, 10, 12]);
+    }
+
+    #[test]
+    fn overflowing_tracks_get_nothing() {
+        let grid = grid(1, 40).row_height(Size::Fill).spacing(1);
+        assert!(grid.heights(Some(32)).iter().all(|&height| height == 0));
+    }
+}
@@ -0,0 +1,185 @@
+use std::marker::PhantomData;
+
+use crate::layout::{share, Alignment, Size};
+use crate::screen::Screen;
+use crate::shape::Drawable;
+
+/// The direction a `Linear` container runs in
+pub trait Axis {
+    /// Split a horizontal and vertical pair into (along, across) the axis
+    fn split(x: i32, y: i32) -> (i32, i32);
+    /// Put an (along, across) pair back into horizontal and vertical
+    fn join(along: i32, across: i32) -> (i32, i32);
+}
+
+pub struct Horizontal;
+pub struct Vertical;
+
+impl Axis for Horizontal {
+    fn split(x: i32, y: i32) -> (i32, i32) { (x, y) }
+    fn join(along: i32, across: i32) -> (i32, i32) { (along, across) }
+}
+
+impl Axis for Vertical {
+    fn split(x: i32, y: i32) -> (i32, i32) { (y, x) }
+    fn join(along: i32, across: i32) -> (i32, i32) { (across, along) }
+}
+
+/// Children one after another along an axis, each sized by its `Size` along it and
+/// placed across it by the container's `Alignment`
+pub struct Linear<A: Axis> {
+    x: i32, y: i32,
+    children: Vec<(Box<dyn Drawable>, Size)>,
+    padding: i32,
+    spacing: i32,
+    align: Alignment,
+    axis: PhantomData<A>,
+}
+
+/// Children side by side, left to right
+pub type Row = Linear<Horizontal>;
+/// Children stacked top to bottom
+pub type Column = Linear<Vertical>;
+
+impl Linear<Horizontal> {
+    pub fn new() -> Row { Linear::empty() }
+}
+
+impl Linear<Vertical> {
+    pub fn new() -> Column { Linear::empty() }
+}
+
+impl<A: Axis> Default for Linear<A> {
+    fn default() -> Linear<A> { Linear::empty() }
+}
+
+impl<A: Axis> Linear<A> {
+    fn empty() -> Linear<A> {
+        Linear {
+            x: 0, y: 0,
+            children: vec![],
+            padding: 0,
+            spacing: 0,
+            align: Alignment::Start,
+            axis: PhantomData,
+        }
+    }
+
+    pub fn push(&mut self, child: Box<dyn Drawable>, size: Size) {
+        self.children.push((child, size));
+    }
+
+    pub fn child(mut self, child: Box<dyn Drawable>, size: Size) -> Linear<A> { self.push(child, size); self }
+    pub fn at(mut self, x: i32, y: i32) -> Linear<A> { self.x = x; self.y = y; self }
+    /// Space between the edge of the container and its children
+    pub fn padding(mut self, padding: i32) -> Linear<A> { self.padding = padding; self }
+    /// Space between one child and the next
+    pub fn spacing(mut self, spacing: i32) -> Linear<A> { self.spacing = spacing; self }
+    /// Where children sit across the axis
+    pub fn align(mut self, align: Alignment) -> Linear<A> { self.align = align; self }
+
+    /// Length of each child along the axis when `length` pixels are available
+    fn lengths(&self, length: Option<i32>) -> Vec<i32> {
+        let mut lengths: Vec<i32> = self.children.iter()
+            .map(|(child, size)| match size {
+                Size::Fixed(length) => *length,
+                Size::Fit | Size::Fill => A::split(child.get_width(), child.get_height()).0,
+            })
+            .collect();
+        let fills = self.children.iter().filter(|(_, size)| *size == Size::Fill).count();
+        if let Some(length) = length.filter(|_| fills > 0) {
+            let used: i32 = self.children.iter().zip(&lengths)
+                .filter(|((_, size), _)| *size != Size::Fill)
+                .map(|(_, length)| length)
+                .sum();
+            let mut shares = share(length - used - self.gaps(), fills);
+            for ((_, size), length) in self.children.iter().zip(lengths.iter_mut()) {
+                if *size == Size::Fill {
+                    *length = shares.next().unwrap_or(0);
+                }
+            }
+        }
+        lengths
+    }
+
+    fn gaps(&self) -> i32 {
+        self.spacing * (self.children.len() as i32 - 1).max(0)
+    }
+
+    /// The (along, across) size that fits every child
+    fn fit(&self) -> (i32, i32) {
+        let along: i32 = self.lengths(None).iter().sum::<i32>() + self.gaps();
+        let across = self.children.iter()
+            .map(|(child, _)| A::split(child.get_width(), child.get_height()).1)
+            .max()
+            .unwrap_or(0);
+        (along + 2 * self.padding, across + 2 * self.padding)
+    }
+}
+
+impl<A: Axis> Drawable for Linear<A> {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        self.draw_in(x, y, self.get_width(), self.get_height(), screen)
+    }
+    fn draw_in(&self, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        let (along, across) = A::split(width, height);
+        let across = across - 2 * self.padding;
+        let mut position = self.padding;
+        for ((child, _), length) in self.children.iter().zip(self.lengths(Some(along - 2 * self.padding))) {
+            let (offset, breadth) = self.align.place(across, A::split(child.get_width(), child.get_height()).1);
+            let (dx, dy) = A::join(position, self.padding + offset);
+            let (child_width, child_height) = A::join(length, breadth);
+            child.draw_in(x + dx, y + dy, child_width, child_height, screen);
+            position += length + self.spacing;
+        }
+    }
+    fn get_x(&self) -> i32 { self.x }
+    fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 {
+        let (along, across) = self.fit();
+        A::join(along, across).0
+    }
+    fn get_height(&self) -> i32 {
+        let (along, across) = self.fit();
+        A::join(along, across).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Space;
+
+    fn row(sizes: &[Size]) -> Row {
+        sizes.iter().fold(Row::new(), |row, &size| row.child(Box::new(Space::new(10, 8)), size))
+    }
+
+    #[test]
+    fn fills_share_what_fixed_and_fit_leave() {
+        let row = row(&[Size::Fixed(20), Size::Fit, Size::Fill, Size::Fill]);
+        assert_eq!(row.lengths(Some(128)), [20, 10, 49, 49]);
+    }
+
+    #[test]
+    fn last_fill_takes_the_remainder() {
+        let row = row(&[Size::Fixed(20), Size::Fit, Size::Fill, Size::Fill, Size::Fill]).spacing(1);
+        // 128 - 30 - 4 gaps = 94
+        assert_eq!(row.lengths(Some(128)), [20, 10, 31, 31, 32]);
+    }
+
+    #[test]
+    fn fills_get_nothing_on_overflow() {
+        let row = row(&[Size::Fixed(100), Size::Fixed(50), Size::Fill]);
+        assert_eq!(row.lengths(Some(128)), [100, 50, 0]);
+    }
+
+    #[test]
+    fn column_splits_height() {
+        let column = [Size::Fit, Size::Fill, Size::Fill].iter()
+            .fold(Column::new().padding(1), |column, &size| column.child(Box::new(Space::new(10, 8)), size));
+        // 30 inside the padding, less the 8 the fit child takes, leaves 22
+        assert_eq!(column.lengths(Some(30)), [8, 11, 11]);
+        assert_eq!((column.get_width(), column.get_height()), (12, 26));
+    }
+}
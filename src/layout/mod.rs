@@ -0,0 +1,144 @@
+//! Containers that place `Drawable`s for you, so views can describe a screen as
+//! rows and columns rather than pixel offsets, and adapt to the panel's size.
+//!
+//! Containers are drawables themselves and can be nested. Drawn with `draw`, a
+//! container takes the size of its contents; drawn with `draw_in`, it lays them
+//! out within the box it is given, handing out any spare room to `Size::Fill`
+//! children. The position a child was given with `at` is ignored.
+
+mod grid;
+mod linear;
+mod stack;
+
+pub use grid::Grid;
+pub use linear::{Column, Linear, Row};
+pub use stack::Stack;
+
+use crate::screen::Screen;
+use crate::shape::Drawable;
+
+/// How much room a child takes along a container's axis
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Size {
+    /// Exactly this many pixels
+    Fixed(i32),
+    /// As much as the child needs
+    Fit,
+    /// An equal share of whatever is left over
+    Fill,
+}
+
+/// Where a child sits within the room it is given
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Alignment {
+    Start,
+    Center,
+    End,
+    /// Give the child all of the room
+    Stretch,
+}
+
+impl Alignment {
+    /// Offset and length of something `length` long placed within `available` pixels
+    fn place(self, available: i32, length: i32) -> (i32, i32) {
+        match self {
+            Alignment::Start => (0, length),
+            Alignment::Center => ((available - length) / 2, length),
+            Alignment::End => (available - length, length),
+            Alignment::Stretch => (0, available),
+        }
+    }
+}
+
+/// Split `spare` pixels between `count` fillers, the last taking any remainder
+fn share(spare: i32, count: usize) -> impl Iterator<Item = i32> {
+    let spare = spare.max(0);
+    let each = if count == 0 { 0 } else { spare / count as i32 };
+    (0..count).map(move |i| if i + 1 == count { spare - each * i as i32 } else { each })
+}
+
+/// Empty room of a given size, to keep things apart
+pub struct Space {
+    width: i32, height: i32,
+}
+
+impl Space {
+    pub fn new(width: i32, height: i32) -> Space { Space { width, height } }
+}
+
+impl Drawable for Space {
+    fn draw(&self, _screen: &mut Screen) {}
+    fn draw_at(&self, _x: i32, _y: i32, _screen: &mut Screen) {}
+    fn get_x(&self) -> i32 { 0 }
+    fn get_y(&self) -> i32 { 0 }
+    fn get_width(&self) -> i32 { self.width }
+    fn get_height(&self) -> i32 { self.height }
+}
+
+/// Keeps its child from drawing outside the box it is laid out in
+pub struct Clip {
+    child: Box<dyn Drawable>,
+}
+
+impl Clip {
+    pub fn new(child: Box<dyn Drawable>) -> Clip { Clip { child } }
+}
+
+impl Drawable for Clip {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(self.get_x(), self.get_y(), screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        self.draw_in(x, y, self.get_width(), self.get_height(), screen)
+    }
+    fn draw_in(&self, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        // stay within any clip already set, such as an outer `Clip`'s, and put it back after
+        let outer = screen.get_clip();
+        let (left, top, right, bottom) = match outer {
+            Some((cx, cy, cw, ch)) => (x.max(cx), y.max(cy), (x + width).min(cx + cw), (y + height).min(cy + ch)),
+            None => (x, y, x + width, y + height),
+        };
+        screen.set_clip(left, top, (right - left).max(0), (bottom - top).max(0));
+        self.child.draw_in(x, y, width, height, screen);
+        screen.restore_clip(outer);
+    }
+    fn get_x(&self) -> i32 { self.child.get_x() }
+    fn get_y(&self) -> i32 { self.child.get_y() }
+    fn get_width(&self) -> i32 { self.child.get_width() }
+    fn get_height(&self) -> i32 { self.child.get_height() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::PanelSize;
+    use crate::shape::{Rect, Rotation};
+
+    #[test]
+    fn share_splits_evenly() {
+        assert_eq!(share(96, 3).collect::<Vec<_>>(), [32, 32, 32]);
+    }
+
+    #[test]
+    fn share_gives_remainder_to_last() {
+        assert_eq!(share(128, 5).collect::<Vec<_>>(), [25, 25, 25, 25, 28]);
+    }
+
+    #[test]
+    fn share_of_overflow_is_nothing() {
+        assert_eq!(share(-20, 2).collect::<Vec<_>>(), [0, 0]);
+        assert_eq!(share(10, 0).count(), 0);
+    }
+
+    #[test]
+    fn nested_clip_stays_within_outer_and_restores_it() {
+        let mut screen = Screen::headless(PanelSize::Panel128x32, Rotation::Deg0);
+        screen.set_clip(0, 0, 64, 32);
+        // the inner clip is laid out 30 wide, past the outer 10
+        let inner = Clip::new(Box::new(Rect::new(30, 30).fill(Some(1))));
+        let outer = Clip::new(Box::new(Column::new().child(Box::new(inner), Size::Fixed(30))));
+        outer.draw_in(0, 0, 10, 10, &mut screen);
+        assert!(screen.get_pixel(5, 5));
+        assert!(!screen.get_pixel(15, 5));
+        assert!(!screen.get_pixel(5, 15));
+        assert_eq!(screen.get_clip(), Some((0, 0, 64, 32)));
+    }
+}
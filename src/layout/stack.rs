@@ -0,0 +1,59 @@
+use crate::layout::Alignment;
+use crate::screen::Screen;
+use crate::shape::Drawable;
+
+/// Children drawn over one another in the order they were added, each placed
+/// within the whole box by its own horizontal and vertical `Alignment`
+pub struct Stack {
+    x: i32, y: i32,
+    children: Vec<(Box<dyn Drawable>, Alignment, Alignment)>,
+    padding: i32,
+}
+
+impl Default for Stack {
+    fn default() -> Stack { Stack::new() }
+}
+
+impl Stack {
+    pub fn new() -> Stack {
+        Stack {
+            x: 0, y: 0,
+            children: vec![],
+            padding: 0,
+        }
+    }
+
+    pub fn push(&mut self, child: Box<dyn Drawable>, horizontal: Alignment, vertical: Alignment) {
+        self.children.push((child, horizontal, vertical));
+    }
+
+    pub fn child(mut self, child: Box<dyn Drawable>, horizontal: Alignment, vertical: Alignment) -> Stack {
+        self.push(child, horizontal, vertical);
+        self
+    }
+    pub fn at(mut self, x: i32, y: i32) -> Stack { self.x = x; self.y = y; self }
+    /// Space between the edge of the stack and its children
+    pub fn padding(mut self, padding: i32) -> Stack { self.padding = padding; self }
+}
+
+impl Drawable for Stack {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        self.draw_in(x, y, self.get_width(), self.get_height(), screen)
+    }
+    fn draw_in(&self, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        for (child, horizontal, vertical) in &self.children {
+            let (dx, child_width) = horizontal.place(width - 2 * self.padding, child.get_width());
+            let (dy, child_height) = vertical.place(height - 2 * self.padding, child.get_height());
+            child.draw_in(x + self.padding + dx, y + self.padding + dy, child_width, child_height, screen);
+        }
+    }
+    fn get_x(&self) -> i32 { self.x }
+    fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 {
+        self.children.iter().map(|(child, _, _)| child.get_width()).max().unwrap_or(0) + 2 * self.padding
+    }
+    fn get_height(&self) -> i32 {
+        self.children.iter().map(|(child, _, _)| child.get_height()).max().unwrap_or(0) + 2 * self.padding
+    }
+}
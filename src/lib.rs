@@ -1,35 +1,150 @@
 pub mod view;
 pub mod views;
 pub mod shape;
+pub mod font;
+pub mod image;
 pub mod screen;
+pub mod display;
 pub mod renderer;
 pub mod buttons;
+pub mod idle;
+pub mod recorder;
+pub mod mirror;
+pub mod theme;
+pub mod layout;
+pub mod widget;
+
+use std::io;
+use std::net::ToSocketAddrs;
 
 use renderer::Renderer;
 use screen::Screen;
 use buttons::ButtonSet;
+use idle::Idle;
+use recorder::{RecordFormat, Recorder};
+use mirror::MirrorServer;
+use theme::Theme;
 
 pub struct GUI {
     pub renderer: Renderer,
     pub screen: Screen,
-    pub buttons: ButtonSet
+    pub buttons: ButtonSet,
+    pub idle: Idle,
+    recorder: Option<Recorder>,
+    // what pressing B and C together records to, if anything
+    record_chord: Option<RecordFormat>,
+    mirror: Option<MirrorServer>
 }
 
 impl GUI {
     pub fn new() -> GUI {
+        GUI::from_parts(Screen::new(), ButtonSet::default_pins())
+    }
+
+    /// Build a GUI around any screen and buttons, such as a headless screen
+    /// driven by virtual buttons
+    pub fn from_parts(screen: Screen, buttons: ButtonSet) -> GUI {
         GUI {
             renderer: Renderer::new(),
-            screen: Screen::new(),
-            buttons: ButtonSet::default_pins()
+            screen,
+            buttons,
+            idle: Idle::new(),
+            recorder: None,
+            record_chord: None,
+            mirror: None
         }
     }
+
     pub fn run(&mut self) {
-        self.screen.display.init().expect("Could not initialise screen.");
+        self.screen.init();
         loop {
-            self.screen.clear();
+            self.step();
+        }
+    }
+
+    /// Handle input and draw a single frame
+    pub fn step(&mut self) {
+        self.screen.clear();
+        self.buttons.poll_all();
+        if let Some(mirror) = &mut self.mirror {
+            mirror.poll(&self.screen, &mut self.buttons);
+        }
+        self.check_record_chord();
+        self.idle.update(&mut self.buttons, &mut self.screen);
+        if self.idle.is_awake() {
             self.renderer.update(&mut self.buttons);
             self.renderer.render(&mut self.screen);
-            self.screen.flush();
+        } else {
+            self.idle.render(&mut self.screen);
+        }
+        self.screen.flush();
+
+        if let Some(mirror) = &mut self.mirror {
+            mirror.send(&self.screen);
+        }
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(err) = recorder.capture(&self.screen) {
+                eprintln!("Stopped recording: {}", err);
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Change how every view is drawn
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.screen.set_theme(theme);
+    }
+
+    /// Stream every frame to clients connecting to `address`, and let them press buttons.
+    /// See `MirrorServer` for the protocol.
+    ///
+    /// Anyone who can connect gets full control of the device, with no authentication.
+    /// Prefer a loopback address such as `127.0.0.1:7878`, reached over an SSH tunnel.
+    pub fn serve_mirror<A: ToSocketAddrs>(&mut self, address: A) -> io::Result<()> {
+        self.mirror = Some(MirrorServer::bind(address)?);
+        Ok(())
+    }
+
+    /// Record every frame shown from now on, until `stop_recording` is called
+    pub fn start_recording(&mut self, format: RecordFormat) -> io::Result<()> {
+        self.stop_recording()?;
+        self.recorder = Some(Recorder::start(format, &self.screen)?);
+        Ok(())
+    }
+
+    /// Stop recording and save the result
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish(),
+            None => Ok(())
+        }
+    }
+
+    pub fn is_recording(&self) -> bool { self.recorder.is_some() }
+
+    /// Let pressing B and C together start and stop recording to `format`
+    pub fn record_on_chord(&mut self, format: RecordFormat) {
+        self.record_chord = Some(format);
+    }
+
+    fn check_record_chord(&mut self) {
+        let format = match &self.record_chord {
+            Some(format) => format.clone(),
+            None => return
+        };
+        let chord = self.buttons.b.is_down() && self.buttons.c.is_down()
+            && (self.buttons.b.has_press() || self.buttons.c.has_press());
+        if !chord {
+            return;
+        }
+        // the chord isn't meant for the view underneath
+        self.buttons.flush();
+        let result = match self.is_recording() {
+            true => self.stop_recording(),
+            false => self.start_recording(format)
+        };
+        if let Err(err) = result {
+            eprintln!("Could not record: {}", err);
         }
     }
 }
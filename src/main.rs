@@ -1,8 +1,10 @@
+use std::any::Any;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, SystemTime};
 
@@ -14,11 +16,28 @@ use reqwest::blocking::Client;
 use serde::Deserialize;
 use serde_json::from_str;
 
+use tinygui::idle::Idle;
+use tinygui::image::{self, Dither, GreyImage, ImageError};
+use tinygui::screen::Screen;
+use tinygui::shape::{Bitmap, Drawable, Text};
 use tinygui::view::{View, ViewSpawner};
-use tinygui::views::{BoidsViewBuilder, Menu, MenuItem, TextInput};
+use tinygui::views::{
+    BoidsViewBuilder, DynamicMenu, Menu, MenuFile, MenuItem, MenuMemory, MenuOrder, MenuRegistry, TextInput,
+};
 use tinygui::GUI;
 
 const URL: &str = "https://slack.com/api/users.profile.set";
+const MENU_FILE: &str = "menu.json";
+const STATE_FILE: &str = "menu_state.json";
+const DEFAULT_MENU: &str = r#"{
+    "entries": [
+        { "type": "view", "label": "Set Slack Status", "view": "slack_status" },
+        { "type": "action", "label": "Take Photo", "action": "take_photo" },
+        { "type": "view", "label": "Photos", "view": "photos" },
+        { "type": "view", "label": "Boids", "view": "boids" },
+        { "type": "view", "label": "Test Keyboard", "view": "keyboard" }
+    ]
+}"#;
 
 #[derive(Deserialize, Clone)]
 struct JSONEntry {
@@ -26,23 +45,6 @@ struct JSONEntry {
     emoji: String,
 }
 
-struct SlackMenuSpawner {
-    entries: Vec<JSONEntry>,
-}
-
-impl ViewSpawner for SlackMenuSpawner {
-    fn spawn(&self) -> Box<dyn View> {
-        let mut menu = Box::new(Menu::new());
-        for entry in self.entries.clone() {
-            menu.add_entry(MenuItem::TextToFunc(
-                entry.text.clone(),
-                Box::new(move || send_status(entry.emoji.clone(), entry.text.clone())),
-            ));
-        }
-        menu
-    }
-}
-
 fn take_photo() {
     let info = info().unwrap();
     if info.cameras.len() < 1 {
@@ -64,6 +66,123 @@ fn take_photo() {
     .unwrap();
 }
 
+/// The photos taken so far, newest first
+fn list_photos() -> Vec<String> {
+    let mut names: Vec<String> = match std::fs::read_dir("pics") {
+        Ok(dir) => dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    // the names start with the date, but day first
+    names.sort_by_key(|name| std::fs::metadata(Path::new("pics").join(name)).and_then(|m| m.modified()).ok());
+    names.reverse();
+    names
+}
+
+fn status_items(statuses: &[JSONEntry]) -> Vec<MenuItem> {
+    statuses
+        .iter()
+        .cloned()
+        .map(|entry| {
+            MenuItem::TextToFunc(
+                entry.text.clone(),
+                Box::new(move || send_status(entry.emoji.clone(), entry.text.clone())),
+            )
+        })
+        .collect()
+}
+
+/// The Slack statuses, most recently used first
+struct SlackStatusSpawner {
+    statuses: Vec<JSONEntry>,
+    memory: MenuMemory,
+}
+
+impl ViewSpawner for SlackStatusSpawner {
+    fn spawn(&self) -> Box<dyn View> {
+        let mut menu = Menu::new()
+            .title("Slack Status".to_owned())
+            .remember(self.memory.clone(), "slack_status".to_owned())
+            .order(MenuOrder::Recent);
+        menu.add_entries(status_items(&self.statuses));
+        Box::new(menu)
+    }
+}
+
+/// A photo shrunk to fit the screen
+struct PhotoView {
+    photo: Result<GreyImage, ImageError>,
+    // dithered on the first render, once the screen size is known
+    fitted: RefCell<Option<Bitmap>>,
+}
+
+fn fit(photo: &GreyImage, width: i32, height: i32) -> Bitmap {
+    let scale = (width as f32 / photo.width as f32).min(height as f32 / photo.height as f32);
+    let w = ((photo.width as f32 * scale) as usize).max(1);
+    let h = ((photo.height as f32 * scale) as usize).max(1);
+    let pixels = (0..w * h)
+        .map(|i| photo.pixels[(i / w * photo.height / h) * photo.width + i % w * photo.width / w])
+        .collect();
+    GreyImage { width: w, height: h, pixels }.to_bitmap(Dither::FloydSteinberg)
+}
+
+impl View for PhotoView {
+    fn render(&self, screen: &mut Screen) {
+        match &self.photo {
+            Ok(photo) => {
+                let mut fitted = self.fitted.borrow_mut();
+                let bitmap = fitted.get_or_insert_with(|| fit(photo, screen.get_width(), screen.get_height()));
+                let x = (screen.get_width() - bitmap.get_width()) / 2;
+                let y = (screen.get_height() - bitmap.get_height()) / 2;
+                bitmap.draw_at(x, y, screen);
+            }
+            Err(err) => {
+                let font = screen.theme().font.clone();
+                Text::new(err.to_string()).font(font).draw_at(0, 0, screen);
+            }
+        }
+    }
+    fn result(&mut self, _result: Box<dyn Any>) {}
+}
+
+struct PhotoSpawner {
+    path: PathBuf,
+}
+
+impl ViewSpawner for PhotoSpawner {
+    fn spawn(&self) -> Box<dyn View> {
+        let photo = std::fs::read(&self.path)
+            .map_err(ImageError::from)
+            .and_then(|bytes| image::decode_jpeg(&bytes))
+            // there's nothing to scale in an empty image, so show why instead
+            .and_then(|photo| match photo.width == 0 || photo.height == 0 {
+                true => Err(ImageError::Format("photo has no pixels".to_owned())),
+                false => Ok(photo),
+            });
+        Box::new(PhotoView { photo, fitted: RefCell::new(None) })
+    }
+}
+
+struct PhotosSpawner;
+
+impl ViewSpawner for PhotosSpawner {
+    fn spawn(&self) -> Box<dyn View> {
+        let menu = DynamicMenu::new(
+            list_photos,
+            Box::new(|name: String| {
+                let path = Path::new("pics").join(&name);
+                (name.clone(), MenuItem::TextToView(name, Box::new(PhotoSpawner { path })))
+            }),
+        )
+        .title("Photos".to_owned())
+        .empty_text("No photos yet".to_owned())
+        .refresh_every(Duration::from_secs(5));
+        Box::new(menu)
+    }
+}
+
 fn main() {
     // New GUI
     let mut gui = GUI::new();
@@ -73,33 +192,35 @@ fn main() {
     let mut contents = String::new();
     file.read_to_string(&mut contents)
         .expect("Could not read statuses.json");
-    let slack_status_menu = SlackMenuSpawner {
-        entries: from_str(contents.as_str()).expect("Could not parse statuses.json"),
+    let statuses: Vec<JSONEntry> = from_str(contents.as_str()).expect("Could not parse statuses.json");
+
+    // Which entries were last selected and used, kept across restarts
+    let memory = MenuMemory::open(STATE_FILE);
+
+    // Names the menu definition can refer to
+    let slack_status = SlackStatusSpawner { statuses: statuses.clone(), memory };
+    let registry = MenuRegistry::new()
+        .items("slack_statuses", Box::new(move || status_items(&statuses)))
+        .view("slack_status", Box::new(slack_status))
+        .action("take_photo", Box::new(take_photo))
+        .view("photos", Box::new(PhotosSpawner))
+        .view("boids", Box::new(BoidsViewBuilder {}))
+        .view("keyboard", Box::new(TextInput::spawner()));
+
+    // Main menu, from menu.json if there is one so it can be rearranged on the device
+    let main_menu = if Path::new(MENU_FILE).exists() {
+        MenuFile::load(MENU_FILE, registry)
+            .expect("Could not load menu.json")
+            .on_reload_error(Box::new(|err| eprintln!("Keeping the current menu: {}", err)))
+    } else {
+        MenuFile::from_json(DEFAULT_MENU, registry).expect("Could not build the default menu")
     };
 
-    // Main menu
-    let mut main_menu = Menu::new();
-
-    // Boids!
-    let boids_builder = BoidsViewBuilder {};
-
-    let text_spawner = TextInput::spawner();
-
-    main_menu.add_entry(MenuItem::TextToView(
-        "Set Slack Status".to_owned(),
-        Box::new(slack_status_menu),
-    ));
-    main_menu.add_entry(MenuItem::TextToFunc(
-        "Take Photo".to_owned(),
-        Box::new(take_photo),
-    ));
-    main_menu.add_entry(MenuItem::TextToView(
-        "Boids".to_owned(),
-        Box::new(boids_builder),
-    ));
-    main_menu.add_entry(MenuItem::TextToView(
-        "Test Keyboard".to_owned(),
-        Box::new(text_spawner)));
+    // Save the screen from burn-in when left alone
+    gui.idle = Idle::new()
+        .dim_after(Duration::from_secs(60))
+        .blank_after(Duration::from_secs(300))
+        .screensaver(Box::new(BoidsViewBuilder {}));
 
     gui.renderer.push_view(Box::new(main_menu));
     gui.run();
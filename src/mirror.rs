@@ -0,0 +1,193 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+
+/// Sent to each client on connecting, followed by the frame width and height
+/// as big-endian u16s
+pub const MAGIC: &[u8] = b"TGUI";
+
+struct Client {
+    stream: TcpStream,
+    // the last frame this client was sent, to encode the next one against
+    previous: Vec<u8>,
+}
+
+/// Streams every frame to clients over TCP, and takes button presses back from them.
+///
+/// After the header, each frame is a big-endian u32 length followed by that many
+/// bytes of run-length encoded `(count, byte)` pairs. Decoded, they are the frame
+/// XORed with the client's previous frame (all zeroes to begin with), packed eight
+/// pixels to a byte, most significant bit first, with rows padded to whole bytes.
+///
+/// Clients press buttons by sending one byte each: `a`, `b`, `c`, or `u`, `d`,
+/// `l`, `r` for the arrows. A client that can't keep up is disconnected.
+///
+/// There is no authentication: anyone who can reach the bound address sees the
+/// screen and controls the device. Bind to `127.0.0.1` and reach it through an SSH
+/// tunnel unless the network is trusted.
+pub struct MirrorServer {
+    listener: TcpListener,
+    clients: Vec<Client>,
+}
+
+impl MirrorServer {
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<MirrorServer> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(MirrorServer { listener, clients: vec![] })
+    }
+
+    pub fn client_count(&self) -> usize { self.clients.len() }
+
+    /// Accept new clients and apply any button presses they have sent
+    pub fn poll(&mut self, screen: &Screen, buttons: &mut ButtonSet) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if let Ok(client) = connect(stream, screen) {
+                        self.clients.push(client);
+                    }
+                }
+                Err(ref err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    eprintln!("Mirror could not accept a client: {}", err);
+                    break;
+                }
+            }
+        }
+
+        self.clients.retain(|client| {
+            let mut stream = &client.stream;
+            let mut input = [0; 16];
+            loop {
+                match stream.read(&mut input) {
+                    // the client hung up
+                    Ok(0) => return false,
+                    Ok(count) => input[..count].iter().for_each(|&key| press(buttons, key)),
+                    Err(ref err) if err.kind() == ErrorKind::WouldBlock => return true,
+                    Err(_) => return false,
+                }
+            }
+        });
+    }
+
+    /// Send the frame on `screen` to every client it differs for
+    pub fn send(&mut self, screen: &Screen) {
+        let frame = pack(screen);
+        let clients = std::mem::take(&mut self.clients);
+        self.clients = clients.into_iter()
+            .filter_map(|mut client| {
+                if client.previous == frame {
+                    return Some(client);
+                }
+                let delta: Vec<u8> = frame.iter().zip(&client.previous).map(|(a, b)| a ^ b).collect();
+                let encoded = run_length_encode(&delta);
+                let mut message = (encoded.len() as u32).to_be_bytes().to_vec();
+                message.extend(encoded);
+                client.previous = frame.clone();
+                match write_all(&mut client.stream, &message) {
+                    Ok(()) => Some(client),
+                    Err(_) => None,
+                }
+            })
+            .collect();
+    }
+}
+
+fn connect(stream: TcpStream, screen: &Screen) -> io::Result<Client> {
+    let (width, height) = screen.frame_size();
+    let mut stream = stream;
+    stream.set_nodelay(true)?;
+    let mut header = MAGIC.to_vec();
+    header.extend(&(width as u16).to_be_bytes());
+    header.extend(&(height as u16).to_be_bytes());
+    stream.write_all(&header)?;
+    stream.set_nonblocking(true)?;
+    let row_bytes = (width as usize).div_ceil(8);
+    Ok(Client { stream, previous: vec![0; row_bytes * height as usize] })
+}
+
+/// Write the whole message, giving up rather than waiting if the client's buffer is full
+fn write_all(stream: &mut TcpStream, mut message: &[u8]) -> io::Result<()> {
+    while !message.is_empty() {
+        match stream.write(message) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(count) => message = &message[count..],
+            Err(ref err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+fn press(buttons: &mut ButtonSet, key: u8) {
+    match key {
+        b'a' => buttons.a.press(),
+        b'b' => buttons.b.press(),
+        b'c' => buttons.c.press(),
+        b'u' => buttons.up.press(),
+        b'd' => buttons.down.press(),
+        b'l' => buttons.left.press(),
+        b'r' => buttons.right.press(),
+        _ => {}
+    }
+}
+
+/// The whole frame, one bit per pixel, rows padded to whole bytes
+fn pack(screen: &Screen) -> Vec<u8> {
+    let (width, height) = screen.frame_size();
+    let row_bytes = (width + 7) / 8;
+    let mut packed = vec![0; (row_bytes * height) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            if screen.get_pixel(x, y) {
+                packed[(y * row_bytes + x / 8) as usize] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+    packed
+}
+
+fn run_length_encode(data: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![];
+    let mut rest = data;
+    while let Some(&byte) = rest.first() {
+        let count = rest.iter().take(255).take_while(|&&b| b == byte).count();
+        encoded.push(count as u8);
+        encoded.push(byte);
+        rest = &rest[count..];
+    }
+    encoded
+}
+
+/// Undo `run_length_encode`, for clients. `None` if the data isn't whole pairs.
+pub fn run_length_decode(data: &[u8]) -> Option<Vec<u8>> {
+    let pairs = data.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    Some(pairs.flat_map(|pair| std::iter::repeat_n(pair[1], pair[0] as usize)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_length_round_trip() {
+        let mut long = vec![7; 600];
+        long.extend(&[0, 1, 1, 2]);
+        for data in &[vec![], vec![5], vec![1, 2, 3], vec![0, 0, 9], long] {
+            assert_eq!(run_length_decode(&run_length_encode(data)).as_ref(), Some(data));
+        }
+    }
+
+    #[test]
+    fn run_length_decode_rejects_odd_input() {
+        assert_eq!(run_length_decode(&[]), Some(vec![]));
+        assert_eq!(run_length_decode(&[3]), None);
+        assert_eq!(run_length_decode(&[2, 9, 1]), None);
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::screen::Screen;
+
+// black for unlit pixels, white for lit ones
+const PALETTE: &[u8] = &[0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF];
+
+/// Where and how a recording is saved
+#[derive(Clone, Debug)]
+pub enum RecordFormat {
+    /// A looping animated GIF at this path
+    Gif(PathBuf),
+    /// Numbered PBM files in this directory, with their timestamps listed in `frames.txt`
+    Pbm(PathBuf),
+}
+
+/// Captures the frames sent to the display along with when they were shown.
+/// A frame identical to the one before it only extends how long that one is shown.
+pub struct Recorder {
+    format: RecordFormat,
+    started: Instant,
+    width: i32,
+    height: i32,
+    // the time each distinct frame was first shown, and its pixels, one byte each
+    frames: Vec<(Duration, Vec<u8>)>,
+    // how many distinct frames have been captured, for numbering PBM files
+    frame_count: usize,
+    // timestamps of PBM frames already written
+    timestamps: Option<File>,
+}
+
+impl Recorder {
+    pub fn start(format: RecordFormat, screen: &Screen) -> io::Result<Recorder> {
+        let timestamps = match &format {
+            RecordFormat::Pbm(dir) => {
+                fs::create_dir_all(dir)?;
+                Some(File::create(dir.join("frames.txt"))?)
+            }
+            RecordFormat::Gif(_) => None,
+        };
+        let (width, height) = screen.frame_size();
+        Ok(Recorder {
+            format,
+            started: Instant::now(),
+            width, height,
+            frames: vec![],
+            frame_count: 0,
+            timestamps,
+        })
+    }
+
+    /// Record the frame currently on `screen`
+    pub fn capture(&mut self, screen: &Screen) -> io::Result<()> {
+        let pixels: Vec<u8> = (0..self.width * self.height)
+            .map(|i| screen.get_pixel(i % self.width, i / self.width) as u8)
+            .collect();
+        if self.frames.last().is_some_and(|(_, last)| *last == pixels) {
+            return Ok(());
+        }
+        let time = self.started.elapsed();
+        self.frame_count += 1;
+
+        if let (RecordFormat::Pbm(dir), Some(timestamps)) = (&self.format, &mut self.timestamps) {
+            let name = format!("{:05}.pbm", self.frame_count);
+            let mut file = File::create(dir.join(&name))?;
+            write!(file, "P1\n{} {}\n", self.width, self.height)?;
+            for row in pixels.chunks(self.width as usize) {
+                let row: Vec<String> = row.iter().map(|pixel| pixel.to_string()).collect();
+                writeln!(file, "{}", row.join(" "))?;
+            }
+            writeln!(timestamps, "{} {:.3}", name, time.as_secs_f32())?;
+            // only the last frame is needed to spot repeats
+            self.frames.clear();
+        }
+        self.frames.push((time, pixels));
+        Ok(())
+    }
+
+    /// Stop recording and write out anything not yet saved
+    pub fn finish(self) -> io::Result<()> {
+        let path = match &self.format {
+            RecordFormat::Gif(path) => path,
+            RecordFormat::Pbm(_) => return Ok(()),
+        };
+        let mut encoder = gif::Encoder::new(File::create(path)?, self.width as u16, self.height as u16, PALETTE)
+            .map_err(gif_error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+
+        let end = self.started.elapsed();
+        for (i, (time, pixels)) in self.frames.iter().enumerate() {
+            let next = self.frames.get(i + 1).map_or(end, |(next, _)| *next);
+            let mut frame = gif::Frame::from_indexed_pixels(self.width as u16, self.height as u16, pixels, None);
+            // GIF delays are in hundredths of a second; rounding both ends keeps the total in step
+            let delay = next.as_millis() / 10 - time.as_millis() / 10;
+            frame.delay = delay.max(1).min(u16::MAX as u128) as u16;
+            encoder.write_frame(&frame).map_err(gif_error)?;
+        }
+        Ok(())
+    }
+}
+
+fn gif_error(err: gif::EncodingError) -> io::Error {
+    io::Error::other(err.to_string())
+}
@@ -1,18 +1,63 @@
+use std::time::{Duration, Instant};
+
 use crate::buttons::ButtonSet;
 use crate::view::{View,UpdateResult::*};
 use crate::screen::Screen;
 
+/// Slowly moves everything drawn around a small square, so static views don't
+/// keep lighting exactly the same pixels
+struct PixelShift {
+    amount: i32,
+    period: Duration,
+    started: Instant,
+}
+
+impl PixelShift {
+    /// The current offset. Positions are visited row by row, alternating direction,
+    /// then in reverse, so each step moves a single pixel.
+    fn offset(&self) -> (i32, i32) {
+        let side = 2 * self.amount + 1;
+        let positions = side * side;
+        if positions == 1 {
+            return (0, 0);
+        }
+        let step = (self.started.elapsed().as_millis() / self.period.as_millis().max(1)) as i32;
+        let cycle = step % (2 * positions - 2);
+        let index = if cycle < positions { cycle } else { 2 * positions - 2 - cycle };
+        let (row, column) = (index / side, index % side);
+        let column = if row % 2 == 0 { column } else { side - 1 - column };
+        (column - self.amount, row - self.amount)
+    }
+}
+
 pub struct Renderer {
     views: Vec<Box<dyn View>>,
+    pixel_shift: Option<PixelShift>,
 }
 
 impl Renderer {
     pub fn new() -> Renderer {
         Renderer {
             views: Vec::new(),
+            pixel_shift: None,
         }
     }
 
+    /// Drift everything views draw by up to `amount` pixels in each direction,
+    /// moving one pixel every `period`. Views see a screen `amount` pixels
+    /// smaller on each side, so nothing is cut off at the panel's edge.
+    pub fn enable_pixel_shift(&mut self, amount: i32, period: Duration) {
+        self.pixel_shift = Some(PixelShift {
+            amount: amount.max(0),
+            period,
+            started: Instant::now(),
+        });
+    }
+
+    pub fn disable_pixel_shift(&mut self) {
+        self.pixel_shift = None;
+    }
+
     pub fn push_view(&mut self, view: Box<dyn View>) {
         self.views.push(view)
     }
@@ -22,10 +67,15 @@ impl Renderer {
     }
 
     pub fn render(&mut self, screen: &mut Screen) {
+        if let Some(shift) = &self.pixel_shift {
+            let (dx, dy) = shift.offset();
+            screen.set_translation(shift.amount, dx, dy);
+        }
         match self.views.last() {
             Some(view) => view.render(screen),
             None => { }
         }
+        screen.clear_translation();
     }
 
     pub fn update(&mut self, buttons: &mut ButtonSet) {
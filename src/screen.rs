@@ -1,41 +1,223 @@
 extern crate embedded_graphics;
 
-use rppal::i2c::I2c;
-use ssd1306::{Builder, mode::GraphicsMode, interface::i2c::I2cInterface};
+use std::path::Path;
 
-const DEFAULT_WIDTH: i32 = 128;
-const DEFAULT_HEIGHT: i32 = 64;
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::pixelcolor::PixelColorU8;
+
+use crate::display::{Display, DisplayConfig, InterfaceConfig};
+use crate::shape::Rotation;
+use crate::theme::Theme;
+
+pub use crate::display::PanelSize;
+
+/// Where `Screen::new` looks for the display's wiring
+const CONFIG_FILE: &str = "display.json";
 
 pub struct Screen {
-    pub display: GraphicsMode<I2cInterface<I2c>>,
-    width: i32,
-    height: i32
+    display: Display,
+    panel: PanelSize,
+    rotation: Rotation,
+    /// One byte per column for each 8 pixel tall page, in the panel's own orientation
+    buffer: Vec<u8>,
+    clip: Option<(i32, i32, i32, i32)>,
+    /// Margin kept free on every side, so drawing can be shifted without losing its edges
+    inset: i32,
+    offset: (i32, i32),
+    theme: Theme
 }
 
 impl Screen {
+    /// Connect to the display described in `display.json`, or to a 128x64 SSD1306
+    /// at I2C address 0x3C if there is no such file
     pub fn new() -> Screen {
-        let mut i2c = I2c::new().expect("Could not create I2C Device");
-        i2c.set_slave_address(0x3c).expect("Could not select device");
+        let config = match Path::new(CONFIG_FILE).exists() {
+            true => DisplayConfig::load(CONFIG_FILE).expect("Could not load display.json"),
+            false => DisplayConfig::default(),
+        };
+        Screen::from_config(&config)
+    }
 
+    pub fn from_config(config: &DisplayConfig) -> Screen {
+        let display = Display::connect(config).expect("Could not connect to display");
+        let rotation = config.rotation().expect("Invalid display rotation");
+        Screen::with_display(display, rotation)
+    }
+
+    /// Connect to an SSD1306 at I2C address 0x3C
+    pub fn with_panel(panel: PanelSize, rotation: Rotation) -> Screen {
+        Screen::with_display(
+            Display::connect(&DisplayConfig { panel, ..DisplayConfig::default() })
+                .expect("Could not connect to display"),
+            rotation)
+    }
+
+    /// A screen that draws into memory only, for running without a panel attached
+    pub fn headless(panel: PanelSize, rotation: Rotation) -> Screen {
+        Screen::with_display(
+            Display::connect(&DisplayConfig {
+                panel,
+                interface: InterfaceConfig::Headless,
+                ..DisplayConfig::default()
+            }).expect("Could not create headless display"),
+            rotation)
+    }
+
+    pub fn with_display(display: Display, rotation: Rotation) -> Screen {
+        let panel = display.panel();
+        let (width, height) = panel.dimensions();
         Screen {
-            display: Builder::new().connect_i2c(i2c).into(),
-            width: DEFAULT_WIDTH,
-            height: DEFAULT_HEIGHT
+            display,
+            panel,
+            rotation,
+            buffer: vec![0; (width * height / 8) as usize],
+            clip: None,
+            inset: 0,
+            offset: (0, 0),
+            theme: Theme::new()
         }
     }
 
-    pub fn set_shape(&mut self, width: i32, height: i32) {
-        self.width = width;
-        self.height = height;
+    /// Send the display's power-on sequence
+    pub fn init(&mut self) {
+        self.display.init().expect("Could not initialise screen.");
     }
 
-    pub fn get_width(&self) -> i32 { self.width }
-    pub fn get_height(&self) -> i32 { self.height }
+    /// Panel brightness, from 0 to 255
+    pub fn set_contrast(&mut self, contrast: u8) {
+        self.display.set_contrast(contrast).expect("Could not set contrast");
+    }
+
+    /// Invert the whole panel in hardware
+    pub fn set_inverted(&mut self, inverted: bool) {
+        self.display.set_inverted(inverted).expect("Could not invert screen");
+    }
+
+    /// Switch the panel on or off; it keeps showing the last frame when switched back on
+    pub fn set_display_on(&mut self, on: bool) {
+        self.display.set_on(on).expect("Could not switch screen on or off");
+    }
+
+    /// The look views should draw with
+    pub fn theme(&self) -> &Theme { &self.theme }
+
+    /// Change the theme. An inverted theme flips every pixel drawn from then on.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Rotate the picture clockwise. With a quarter turn, width and height swap over.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    pub fn get_rotation(&self) -> Rotation { self.rotation }
+    pub fn get_panel(&self) -> PanelSize { self.panel }
+
+    /// Width as seen by views, after rotation and less any inset
+    pub fn get_width(&self) -> i32 { self.frame_size().0 - 2 * self.inset }
+    /// Height as seen by views, after rotation and less any inset
+    pub fn get_height(&self) -> i32 { self.frame_size().1 - 2 * self.inset }
+
+    /// Size of the whole frame after rotation, ignoring any inset
+    pub fn frame_size(&self) -> (i32, i32) {
+        let (width, height) = self.panel.dimensions();
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => (width, height),
+            Rotation::Deg90 | Rotation::Deg270 => (height, width),
+        }
+    }
 
+    /// Shrink the area views draw in by `inset` pixels on every side, then move it
+    /// by `(dx, dy)`. Keeping the offset within the inset means nothing near the
+    /// edges is cut off by the panel.
+    pub fn set_translation(&mut self, inset: i32, dx: i32, dy: i32) {
+        self.inset = inset;
+        self.offset = (dx, dy);
+    }
+
+    pub fn clear_translation(&mut self) {
+        self.set_translation(0, 0, 0);
+    }
+
+    /// Only draw pixels inside the given rectangle until `clear_clip` is called
+    pub fn set_clip(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.clip = Some((x, y, width, height));
+    }
+
+    pub fn clear_clip(&mut self) {
+        self.clip = None;
+    }
+
+    /// The clip rectangle as (x, y, width, height), if there is one
+    pub fn get_clip(&self) -> Option<(i32, i32, i32, i32)> { self.clip }
+
+    /// Put back a clip rectangle saved with `get_clip`
+    pub fn restore_clip(&mut self, clip: Option<(i32, i32, i32, i32)>) {
+        self.clip = clip;
+    }
+
+    /// Draw pixels to the display, dropping any outside the clip rectangle.
+    /// Coordinates are relative to the translated area set by `set_translation`.
+    pub fn draw<T>(&mut self, pixels: T)
+    where
+        T: Iterator<Item = Pixel<PixelColorU8>>,
+    {
+        for Pixel(coord, color) in pixels {
+            let (x, y) = (coord[0] as i32, coord[1] as i32);
+            if let Some((cx, cy, width, height)) = self.clip {
+                if x < cx || y < cy || x >= cx + width || y >= cy + height {
+                    continue;
+                }
+            }
+            let (dx, dy) = self.offset;
+            let lit = (color.into_inner() != 0) != self.theme.inverted;
+            self.set_pixel(x + self.inset + dx, y + self.inset + dy, lit);
+        }
+    }
+
+    /// Whether the pixel at (x, y) of the whole frame is lit, ignoring any translation
+    pub fn get_pixel(&self, x: i32, y: i32) -> bool {
+        match self.buffer_index(x, y) {
+            Some((index, bit)) => self.buffer[index] & bit != 0,
+            None => false,
+        }
+    }
+
+    fn set_pixel(&mut self, x: i32, y: i32, on: bool) {
+        if let Some((index, bit)) = self.buffer_index(x, y) {
+            if on {
+                self.buffer[index] |= bit;
+            } else {
+                self.buffer[index] &= !bit;
+            }
+        }
+    }
+
+    /// The byte and bit in the frame buffer behind the pixel at (x, y) of the whole frame
+    fn buffer_index(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        let (frame_width, frame_height) = self.frame_size();
+        if x < 0 || y < 0 || x >= frame_width || y >= frame_height {
+            return None;
+        }
+        let (width, height) = self.panel.dimensions();
+        let (px, py) = match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (width - 1 - y, x),
+            Rotation::Deg180 => (width - 1 - x, height - 1 - y),
+            Rotation::Deg270 => (y, height - 1 - x),
+        };
+        Some(((py / 8 * width + px) as usize, 1 << (py % 8)))
+    }
+
+    /// Blank the frame to the theme's background
     pub fn clear(&mut self) {
-        self.display.clear()
+        let background = if self.theme.inverted { 0xFF } else { 0x00 };
+        for byte in self.buffer.iter_mut() {
+            *byte = background;
+        }
     }
     pub fn flush(&mut self) {
-        self.display.flush().unwrap()
+        self.display.flush(&self.buffer).unwrap()
     }
 }
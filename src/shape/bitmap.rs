@@ -1,23 +1,90 @@
-use crate::{screen::Screen, shape::Drawable};
+use std::path::Path;
+
+use crate::{screen::Screen, shape::{to_pixel, Drawable}};
+use crate::image::{self, Dither, ImageError};
 use embedded_graphics::prelude::*;
 use embedded_graphics::coord::Coord;
 use embedded_graphics::image::Image1BPP;
 
+/// Clockwise rotation in quarter turns
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+#[derive(Clone)]
 pub struct Bitmap {
     x: i32, y: i32,
     width: i32, height: i32,
-    image: Vec<u8>
+    image: Vec<u8>,
+    mask: Option<Box<Bitmap>>
 }
 
 impl Bitmap {
+    /// Wrap `image`, one bit per pixel in rows padded to whole bytes. Panics if
+    /// it's too short for `width` x `height` pixels.
     pub fn new(image: &[u8], width: i32, height: i32) -> Bitmap {
+        assert!(width >= 0 && height >= 0, "Bitmap size {}x{} is negative", width, height);
+        let needed = ((width + 7) / 8 * height) as usize;
+        assert!(image.len() >= needed,
+            "Bitmap of {}x{} needs {} bytes, but was given {}", width, height, needed, image.len());
         Bitmap {
             x: 0, y: 0,
             width, height,
-            image: Vec::from(image)
+            image: Vec::from(image),
+            mask: None
+        }
+    }
+
+    /// Build a bitmap from one bool per pixel, row by row
+    pub fn from_pixels(pixels: &[bool], width: i32, height: i32) -> Bitmap {
+        let stride = ((width + 7) / 8) as usize;
+        let mut image = vec![0; stride * height as usize];
+        for (i, _) in pixels.iter().enumerate().filter(|(_, &lit)| lit) {
+            let (x, y) = (i % width as usize, i / width as usize);
+            image[y * stride + x / 8] |= 0x80 >> (x % 8);
+        }
+        Bitmap {
+            x: 0, y: 0,
+            width, height,
+            image,
+            mask: None
+        }
+    }
+
+    /// Decode a PBM, 1-bit BMP, PNG or JPEG image, such as one embedded with `include_bytes!`
+    pub fn decode(bytes: &[u8], dither: Dither) -> Result<Bitmap, ImageError> {
+        image::decode(bytes, dither)
+    }
+
+    /// Load a PBM, 1-bit BMP, PNG or JPEG image from disk
+    pub fn load<P: AsRef<Path>>(path: P, dither: Dither) -> Result<Bitmap, ImageError> {
+        image::load(path, dither)
+    }
+
+    /// Whether the pixel at (x, y) is lit
+    pub fn get_pixel(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
         }
+        let stride = (self.width + 7) / 8;
+        self.image[(y * stride + x / 8) as usize] & (0x80 >> (x % 8)) != 0
     }
 
+    /// Copy out a rectangular region of the bitmap
+    pub fn crop(&self, x: i32, y: i32, width: i32, height: i32) -> Bitmap {
+        let pixels: Vec<bool> = (0..width * height)
+            .map(|i| self.get_pixel(x + i % width, y + i / width))
+            .collect();
+        Bitmap::from_pixels(&pixels, width, height)
+    }
+
+    pub fn get_width(&self) -> i32 { self.width }
+    pub fn get_height(&self) -> i32 { self.height }
+
     pub fn invert(mut self, invert: bool) -> Bitmap {
         self.image = self.image.iter()
                                .map(|row| match invert { true => !row, false => *row })
@@ -26,17 +93,110 @@ impl Bitmap {
     }
 
     pub fn at(mut self, x: i32, y: i32) -> Bitmap { self.x = x; self.y = y; self }
+
+    /// Only draw the pixels that are lit in `mask`, leaving the rest of the screen untouched
+    pub fn mask(mut self, mask: Bitmap) -> Bitmap { self.mask = Some(Box::new(mask)); self }
+
+    /// Only draw lit pixels, so the bitmap can be laid over other content
+    pub fn transparent(self) -> Bitmap {
+        let mask = self.clone();
+        self.mask(mask)
+    }
+
+    /// Draw lit pixels with a one pixel unlit border around them, so the bitmap stays
+    /// readable over other content without blanking its whole rectangle
+    pub fn outlined(self) -> Bitmap {
+        let pixels: Vec<bool> = (0..self.width * self.height)
+            .map(|i| {
+                let (x, y) = (i % self.width, i / self.width);
+                (-1..=1).any(|dy| (-1..=1).any(|dx| self.get_pixel(x + dx, y + dy)))
+            })
+            .collect();
+        let mask = Bitmap::from_pixels(&pixels, self.width, self.height);
+        self.mask(mask)
+    }
+
+    /// Build a new bitmap of the given size, taking each pixel from `source(x, y)`.
+    /// The mask is transformed the same way.
+    fn transform(&self, width: i32, height: i32, source: &dyn Fn(i32, i32) -> (i32, i32)) -> Bitmap {
+        let pixels: Vec<bool> = (0..width * height)
+            .map(|i| {
+                let (sx, sy) = source(i % width, i / width);
+                self.get_pixel(sx, sy)
+            })
+            .collect();
+        let mut bitmap = Bitmap::from_pixels(&pixels, width, height).at(self.x, self.y);
+        bitmap.mask = self.mask.as_ref()
+            .map(|mask| Box::new(mask.transform(width, height, source)));
+        bitmap
+    }
+
+    pub fn flip_horizontal(self) -> Bitmap {
+        let width = self.width;
+        self.transform(self.width, self.height, &|x, y| (width - 1 - x, y))
+    }
+
+    pub fn flip_vertical(self) -> Bitmap {
+        let height = self.height;
+        self.transform(self.width, self.height, &|x, y| (x, height - 1 - y))
+    }
+
+    pub fn rotate(self, rotation: Rotation) -> Bitmap {
+        let (width, height) = (self.width, self.height);
+        match rotation {
+            Rotation::Deg0 => self,
+            Rotation::Deg90 => self.transform(height, width, &|x, y| (y, height - 1 - x)),
+            Rotation::Deg180 => self.transform(width, height, &|x, y| (width - 1 - x, height - 1 - y)),
+            Rotation::Deg270 => self.transform(height, width, &|x, y| (width - 1 - y, x)),
+        }
+    }
+
+    /// Enlarge by a whole number factor, repeating each pixel
+    pub fn scale(self, factor: i32) -> Bitmap {
+        let factor = factor.max(1);
+        self.transform(self.width * factor, self.height * factor, &|x, y| (x / factor, y / factor))
+    }
 }
 
 impl<'a> Drawable for Bitmap {
     fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
     fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
-        screen.display.draw(
-            Image1BPP::new(self.image.as_slice(), self.width as u32, self.height as u32)
-            .translate(Coord::new(x, y))
-            .into_iter());
+        match &self.mask {
+            Some(mask) => {
+                let pixels: Vec<_> = (0..self.width * self.height)
+                    .map(|i| (i % self.width, i / self.width))
+                    .filter(|&(px, py)| mask.get_pixel(px, py))
+                    .filter_map(|(px, py)| to_pixel(x + px, y + py, self.get_pixel(px, py) as u8))
+                    .collect();
+                screen.draw(pixels.into_iter());
+            }
+            None => screen.draw(
+                Image1BPP::new(self.image.as_slice(), self.width as u32, self.height as u32)
+                .translate(Coord::new(x, y))
+                .into_iter()),
+        }
     }
     fn get_x(&self) -> i32 { self.x }
     fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { self.width }
+    fn get_height(&self) -> i32 { self.height }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rows_padded_to_whole_bytes() {
+        let bitmap = Bitmap::new(&[0b1000_0000, 0b0100_0000, 0b0000_0010, 0b0000_0000], 10, 2);
+        assert!(bitmap.get_pixel(0, 0) && bitmap.get_pixel(9, 0));
+        assert!(bitmap.get_pixel(6, 1) && !bitmap.get_pixel(0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Bitmap of 10x2 needs 4 bytes, but was given 3")]
+    fn rejects_short_images() {
+        Bitmap::new(&[0; 3], 10, 2);
+    }
+}
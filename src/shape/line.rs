@@ -28,7 +28,7 @@ impl Line {
 impl Drawable for Line {
     fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
     fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
-        screen.display.draw(
+        screen.draw(
             primitives::Line::new(
                 Coord::new(x, y),
                 Coord::new(x + self.width, y + self.height))
@@ -37,5 +37,7 @@ impl Drawable for Line {
     }
     fn get_x(&self) -> i32 { self.x }
     fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { self.width.abs() + 1 }
+    fn get_height(&self) -> i32 { self.height.abs() + 1 }
 }
 
@@ -3,14 +3,18 @@ mod bitmap;
 mod line;
 mod text;
 mod section;
+mod sprite;
 
 pub use rect::Rect;
-pub use bitmap::Bitmap;
+pub use bitmap::{Bitmap, Rotation};
 pub use section::Section;
-pub use text::Text;
+pub use sprite::{AnimatedBitmap, AnimationMode, SpriteSheet};
+pub use text::{Align, Text};
 pub use line::Line;
 
+use embedded_graphics::drawable::Pixel;
 use embedded_graphics::pixelcolor::PixelColorU8;
+use embedded_graphics::unsignedcoord::UnsignedCoord;
 
 use crate::screen::Screen;
 
@@ -21,9 +25,27 @@ pub fn to_color(color: Option<u8>) -> Option<PixelColorU8>{
     }
 }
 
+/// Build a single pixel, or nothing if it falls off the top or left of the screen
+pub fn to_pixel(x: i32, y: i32, color: u8) -> Option<Pixel<PixelColorU8>> {
+    if x < 0 || y < 0 {
+        None
+    } else {
+        Some(Pixel(UnsignedCoord::new(x as u32, y as u32), PixelColorU8(color)))
+    }
+}
+
 pub trait Drawable {
     fn draw(&self, screen: &mut Screen);
     fn draw_at(&self, x: i32, y: i32, screen: &mut Screen);
+    /// Draw within a `width` x `height` box, as placed by a layout. Shapes with a
+    /// fixed size just draw at its top left corner.
+    fn draw_in(&self, x: i32, y: i32, _width: i32, _height: i32, screen: &mut Screen) {
+        self.draw_at(x, y, screen)
+    }
     fn get_x(&self) -> i32;
     fn get_y(&self) -> i32;
+    /// Pixels the shape covers across, for layouts to fit it
+    fn get_width(&self) -> i32 { 0 }
+    /// Pixels the shape covers down
+    fn get_height(&self) -> i32 { 0 }
 }
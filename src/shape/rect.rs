@@ -31,7 +31,7 @@ impl Rect {
 impl Drawable for Rect {
     fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
     fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
-        screen.display.draw(
+        screen.draw(
             primitives::Rect::new(
                 Coord::new(x, y),
                 Coord::new(x + self.width, y + self.height))
@@ -41,4 +41,6 @@ impl Drawable for Rect {
     }
     fn get_x(&self) -> i32 { self.x }
     fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { self.width + 1 }
+    fn get_height(&self) -> i32 { self.height + 1 }
 }
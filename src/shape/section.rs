@@ -46,4 +46,6 @@ impl Drawable for Section {
 
     fn get_x(&self) -> i32 { self.x }
     fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { self.width + 1 }
+    fn get_height(&self) -> i32 { self.height + 1 }
 }
@@ -0,0 +1,151 @@
+use std::time::{Duration, Instant};
+
+use crate::{screen::Screen, shape::{Bitmap, Drawable}};
+use crate::image::Dither;
+
+const SPINNER: &[u8] = include_bytes!("sprites/spinner.pbm");
+const BATTERY: &[u8] = include_bytes!("sprites/battery.pbm");
+const CURSOR: &[u8] = include_bytes!("sprites/cursor.pbm");
+
+/// A bitmap sliced into equally sized frames, read left to right then top to bottom
+pub struct SpriteSheet {
+    frames: Vec<Bitmap>,
+}
+
+impl SpriteSheet {
+    /// Panics unless the frames are at least a pixel each way
+    pub fn new(sheet: &Bitmap, frame_width: i32, frame_height: i32) -> SpriteSheet {
+        assert!(frame_width > 0 && frame_height > 0,
+            "SpriteSheet frames of {}x{} are empty", frame_width, frame_height);
+        let columns = sheet.get_width() / frame_width;
+        let rows = sheet.get_height() / frame_height;
+        SpriteSheet {
+            frames: (0..rows)
+                .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                .map(|(column, row)| sheet.crop(
+                    column * frame_width, row * frame_height,
+                    frame_width, frame_height))
+                .collect()
+        }
+    }
+
+    fn built_in(pbm: &[u8], frame_width: i32, frame_height: i32) -> SpriteSheet {
+        let sheet = Bitmap::decode(pbm, Dither::default()).expect("Built-in sprite sheet is invalid");
+        SpriteSheet::new(&sheet, frame_width, frame_height)
+    }
+
+    /// An 8x8 turning bar
+    pub fn spinner() -> SpriteSheet { SpriteSheet::built_in(SPINNER, 8, 8) }
+    /// A 12x8 battery filling up bar by bar
+    pub fn battery_charging() -> SpriteSheet { SpriteSheet::built_in(BATTERY, 12, 8) }
+    /// A 1x8 text cursor and a blank frame
+    pub fn cursor() -> SpriteSheet { SpriteSheet::built_in(CURSOR, 1, 8) }
+
+    pub fn frame(&self, i: usize) -> &Bitmap { &self.frames[i] }
+    pub fn len(&self) -> usize { self.frames.len() }
+    pub fn is_empty(&self) -> bool { self.frames.is_empty() }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AnimationMode {
+    /// Start again from the first frame after the last
+    Loop,
+    /// Play forwards then backwards
+    PingPong,
+    /// Play once and stay on the last frame
+    Once,
+}
+
+/// A sprite sheet that draws a different frame as time passes
+pub struct AnimatedBitmap {
+    x: i32, y: i32,
+    frames: Vec<Bitmap>,
+    frame_duration: Duration,
+    mode: AnimationMode,
+    started: Instant,
+}
+
+impl AnimatedBitmap {
+    pub fn new(sheet: SpriteSheet, frame_duration: Duration) -> AnimatedBitmap {
+        AnimatedBitmap {
+            x: 0, y: 0,
+            frames: sheet.frames,
+            frame_duration,
+            mode: AnimationMode::Loop,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn invert(mut self, invert: bool) -> AnimatedBitmap {
+        self.frames = self.frames.into_iter().map(|frame| frame.invert(invert)).collect();
+        self
+    }
+
+    pub fn at(mut self, x: i32, y: i32) -> AnimatedBitmap { self.x = x; self.y = y; self }
+    pub fn mode(mut self, mode: AnimationMode) -> AnimatedBitmap { self.mode = mode; self }
+
+    /// Play the animation from the first frame again
+    pub fn restart(&mut self) { self.started = Instant::now(); }
+
+    /// The frame to show right now
+    pub fn current(&self) -> Option<&Bitmap> { self.frames.get(self.current_frame()) }
+
+    /// The index of the frame to show right now
+    pub fn current_frame(&self) -> usize {
+        let count = self.frames.len();
+        if count < 2 {
+            return 0;
+        }
+        let ticks = (self.started.elapsed().as_millis() / self.frame_duration.as_millis().max(1)) as usize;
+        match self.mode {
+            AnimationMode::Loop => ticks % count,
+            AnimationMode::PingPong => {
+                let period = 2 * (count - 1);
+                let tick = ticks % period;
+                if tick < count { tick } else { period - tick }
+            }
+            AnimationMode::Once => ticks.min(count - 1),
+        }
+    }
+}
+
+impl Drawable for AnimatedBitmap {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        if let Some(frame) = self.current() {
+            frame.draw_at(x, y, screen);
+        }
+    }
+    fn get_x(&self) -> i32 { self.x }
+    fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { self.frames.first().map_or(0, |frame| frame.get_width()) }
+    fn get_height(&self) -> i32 { self.frames.first().map_or(0, |frame| frame.get_height()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slices_frames_left_to_right_then_down() {
+        // a 4x2 sheet of 2x1 frames; only the last frame has a lit pixel
+        let sheet = Bitmap::from_pixels(&[false, false, false, false, false, false, false, true], 4, 2);
+        let sprites = SpriteSheet::new(&sheet, 2, 1);
+        assert_eq!(sprites.len(), 4);
+        assert!(sprites.frame(3).get_pixel(1, 0));
+        assert!((0..3).all(|i| !sprites.frame(i).get_pixel(1, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "SpriteSheet frames of 0x8 are empty")]
+    fn rejects_empty_frames() {
+        SpriteSheet::new(&Bitmap::from_pixels(&[], 8, 8), 0, 8);
+    }
+
+    #[test]
+    fn built_in_sheets_slice_cleanly() {
+        assert!(!SpriteSheet::spinner().is_empty());
+        assert!(!SpriteSheet::battery_charging().is_empty());
+        assert_eq!(SpriteSheet::cursor().len(), 2);
+    }
+}
@@ -1,13 +1,39 @@
-use crate::{screen::Screen, shape::{to_color, Drawable}};
-use embedded_graphics::prelude::*;
-use embedded_graphics::coord::Coord;
-use embedded_graphics::fonts::{Font,Font6x8};
+use std::cmp::max;
+
+use crate::{screen::Screen, shape::{to_pixel, Drawable}};
+use crate::font::{tokenize, BitmapFont, Font, Token};
+
+/// Horizontal alignment of each line of a `Text` within its box
+#[derive(Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    /// How far in from the left a line `width` pixels wide starts within `box_width`
+    fn offset(self, box_width: i32, width: i32) -> i32 {
+        match self {
+            Align::Left => 0,
+            Align::Center => (box_width - width) / 2,
+            Align::Right => box_width - width,
+        }
+    }
+}
 
 pub struct Text {
     x: i32, y: i32,
     text: String,
+    font: Font,
     fill: Option<u8>,
     stroke: Option<u8>,
+    max_width: Option<i32>,
+    max_height: Option<i32>,
+    align: Align,
+    wrap: bool,
+    ellipsis: bool,
+    emoji: bool,
 }
 
 impl Text {
@@ -15,27 +41,257 @@ impl Text {
         Text {
             x: 0, y: 0,
             text,
+            font: Font::default(),
             fill: None,
             stroke: Some(1),
+            max_width: None,
+            max_height: None,
+            align: Align::Left,
+            wrap: false,
+            ellipsis: false,
+            emoji: false,
         }
     }
 
     pub fn set_text(mut self, text: String) -> Text { self.text = text; self }
     pub fn at(mut self, x: i32, y: i32) -> Text { self.x = x; self.y = y; self }
+    pub fn font(mut self, font: Font) -> Text { self.font = font; self }
     pub fn stroke(mut self, stroke: Option<u8>) -> Text { self.stroke = stroke; self }
     pub fn fill(mut self, fill: Option<u8>) -> Text { self.fill = fill; self }
+
+    /// Width of the box the text is aligned, wrapped and truncated within
+    pub fn max_width(mut self, width: i32) -> Text { self.max_width = Some(width); self }
+    /// Height of the box; lines that don't fit are dropped
+    pub fn max_height(mut self, height: i32) -> Text { self.max_height = Some(height); self }
+    pub fn align(mut self, align: Align) -> Text { self.align = align; self }
+    /// Break lines at spaces so they fit within `max_width`
+    pub fn wrap(mut self, wrap: bool) -> Text { self.wrap = wrap; self }
+    /// End text that doesn't fit the box with an ellipsis
+    pub fn ellipsis(mut self, ellipsis: bool) -> Text { self.ellipsis = ellipsis; self }
+    /// Draw known emoji shortcodes such as `:coffee:` as icons
+    pub fn emoji(mut self, emoji: bool) -> Text { self.emoji = emoji; self }
+
+    /// Width in pixels of the widest line, after wrapping and truncation
+    pub fn get_width(&self) -> i32 {
+        let font = self.font.bitmap();
+        self.lines(&font).iter().map(|line| line_width(line)).max().unwrap_or(0)
+    }
+
+    /// Height in pixels of all lines, after wrapping and truncation
+    pub fn get_height(&self) -> i32 {
+        let font = self.font.bitmap();
+        self.lines(&font).len() as i32 * font.line_height()
+    }
+
+    fn lines(&self, font: &BitmapFont) -> Vec<Vec<Token>> {
+        let mut lines = vec![];
+        for paragraph in self.text.split('\n') {
+            let tokens = tokenize(font, paragraph, self.emoji);
+            match self.max_width {
+                Some(width) if self.wrap => lines.extend(wrap(tokens, width)),
+                _ => lines.push(tokens),
+            }
+        }
+
+        let mut truncated = false;
+        if let Some(height) = self.max_height {
+            let max_lines = max(1, height / font.line_height()) as usize;
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+                truncated = true;
+            }
+        }
+
+        if self.ellipsis {
+            let ellipsis = tokenize(font, font.ellipsis(), false);
+            let last = lines.len() - 1;
+            for (i, line) in lines.iter_mut().enumerate() {
+                ellipsize(line, &ellipsis, self.max_width, truncated && i == last);
+            }
+        }
+        lines
+    }
+
+    /// Draw each line aligned within `box_width`, or within the widest line
+    fn draw_aligned(&self, x: i32, y: i32, box_width: Option<i32>, screen: &mut Screen) {
+        let font = self.font.bitmap();
+        let lines = self.lines(&font);
+        let widths: Vec<i32> = lines.iter().map(|line| line_width(line)).collect();
+        let box_width = box_width.unwrap_or_else(|| widths.iter().cloned().max().unwrap_or(0));
+        for (i, line) in lines.iter().enumerate() {
+            let dx = self.align.offset(box_width, widths[i]);
+            self.draw_line(&font, line, x + dx, y + i as i32 * font.line_height(), screen);
+        }
+    }
+
+    fn draw_line(&self, font: &BitmapFont, line: &[Token], x: i32, y: i32, screen: &mut Screen) {
+        let mut pixels = vec![];
+        let mut pen = x;
+        for glyph in line.iter().flat_map(|token| token.glyphs.iter()) {
+            if let Some(fill) = self.fill {
+                for gy in 0..font.line_height() {
+                    for gx in 0..glyph.advance {
+                        pixels.extend(to_pixel(pen + gx, y + gy, fill));
+                    }
+                }
+            }
+            if let Some(stroke) = self.stroke {
+                for gy in 0..glyph.height {
+                    for gx in 0..glyph.width {
+                        if glyph.is_set(gx, gy) {
+                            pixels.extend(to_pixel(
+                                pen + glyph.x_offset + gx,
+                                y + glyph.y_offset + gy,
+                                stroke));
+                        }
+                    }
+                }
+            }
+            pen += glyph.advance;
+        }
+        screen.draw(pixels.into_iter());
+    }
+}
+
+fn line_width(line: &[Token]) -> i32 {
+    line.iter().map(Token::width).sum()
+}
+
+/// Greedily break a paragraph into lines no wider than `width`, splitting words
+/// that are too long to fit on a line by themselves.
+fn wrap(tokens: Vec<Token>, width: i32) -> Vec<Vec<Token>> {
+    let space = tokens.iter().find(|token| token.space).cloned();
+    let mut lines = vec![];
+    let mut line: Vec<Token> = vec![];
+    for word in tokens.split(|token| token.space) {
+        let gap = match (&space, line.is_empty()) {
+            (Some(space), false) => space.width(),
+            _ => 0,
+        };
+        if line_width(&line) + gap + line_width(word) <= width {
+            if gap > 0 {
+                line.extend(space.clone());
+            }
+            line.extend(word.iter().cloned());
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(line);
+            line = vec![];
+        }
+        for token in word {
+            line.push(token.clone());
+            if line_width(&line) > width && line.len() > 1 {
+                let overflow = line.pop().unwrap();
+                lines.push(line);
+                line = vec![overflow];
+            }
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Shorten a line so that it and an ellipsis fit within `width`. Lines that
+/// already fit are left alone unless `force` is set.
+fn ellipsize(line: &mut Vec<Token>, ellipsis: &[Token], width: Option<i32>, force: bool) {
+    let width = width.unwrap_or(i32::MAX);
+    if !force && line_width(line) <= width {
+        return;
+    }
+    while !line.is_empty() && line_width(line) + line_width(ellipsis) > width {
+        line.pop();
+    }
+    while line.last().is_some_and(|token| token.space) {
+        line.pop();
+    }
+    line.extend(ellipsis.iter().cloned());
 }
 
 impl Drawable for Text {
     fn draw(&self, screen: &mut Screen) { self.draw_at(self.x, self.y, screen) }
     fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
-        screen.display.draw(
-            Font6x8::render_str(self.text.as_str())
-            .with_stroke(to_color(self.stroke))
-            .with_fill(to_color(self.fill))
-            .translate(Coord::new(x, y))
-            .into_iter());
+        self.draw_aligned(x, y, self.max_width, screen)
+    }
+    /// Without a `max_width`, lines are aligned within the layout's box instead
+    fn draw_in(&self, x: i32, y: i32, width: i32, _height: i32, screen: &mut Screen) {
+        self.draw_aligned(x, y, self.max_width.or(Some(width)), screen)
     }
     fn get_x(&self) -> i32 { self.x }
     fn get_y(&self) -> i32 { self.y }
+    fn get_width(&self) -> i32 { Text::get_width(self) }
+    fn get_height(&self) -> i32 { Text::get_height(self) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::PanelSize;
+    use crate::shape::Rotation;
+
+    fn char_width() -> i32 { Text::new("a".to_owned()).get_width() }
+    fn line_height() -> i32 { Font::default().bitmap().line_height() }
+
+    fn line_lengths(text: &Text) -> Vec<usize> {
+        let font = text.font.bitmap();
+        text.lines(&font).iter().map(|line| line.len()).collect()
+    }
+
+    #[test]
+    fn splits_a_word_longer_than_the_box() {
+        let text = Text::new("abcdefghijkl".to_owned()).wrap(true).max_width(5 * char_width());
+        assert_eq!(line_lengths(&text), [5, 5, 2]);
+        assert_eq!(text.get_width(), 5 * char_width());
+        assert_eq!(text.get_height(), 3 * line_height());
+    }
+
+    #[test]
+    fn text_that_exactly_fits_is_left_alone() {
+        let width = 6 * char_width();
+        let text = Text::new("abc de".to_owned()).wrap(true).ellipsis(true).max_width(width);
+        assert_eq!(line_lengths(&text), [6]);
+        assert_eq!(text.get_width(), width);
+        // one pixel less and the last word moves down
+        let text = Text::new("abc de".to_owned()).wrap(true).max_width(width - 1);
+        assert_eq!(line_lengths(&text), [3, 2]);
+    }
+
+    #[test]
+    fn box_narrower_than_the_ellipsis_leaves_only_the_ellipsis() {
+        let font = Font::default().bitmap();
+        let ellipsis = Text::new(font.ellipsis().to_owned()).get_width();
+        let text = Text::new("abcdef".to_owned()).ellipsis(true).max_width(ellipsis - 1);
+        assert_eq!(text.get_width(), ellipsis);
+        assert_eq!(line_lengths(&text), [font.ellipsis().chars().count()]);
+    }
+
+    #[test]
+    fn ellipsis_marks_dropped_lines() {
+        let text = Text::new("ab\ncd\nef".to_owned()).ellipsis(true).max_width(10 * char_width())
+            .max_height(2 * line_height());
+        let font = text.font.bitmap();
+        let lines = text.lines(&font);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].len() > 2);
+    }
+
+    #[test]
+    fn aligns_lines_within_the_box() {
+        assert_eq!(Align::Left.offset(30, 10), 0);
+        assert_eq!(Align::Center.offset(30, 10), 10);
+        assert_eq!(Align::Center.offset(30, 11), 9);
+        assert_eq!(Align::Right.offset(30, 10), 20);
+    }
+
+    #[test]
+    fn right_aligned_text_draws_at_the_right_of_its_box() {
+        let mut screen = Screen::headless(PanelSize::Panel128x32, Rotation::Deg0);
+        let width = 10 * char_width();
+        Text::new("ab".to_owned()).align(Align::Right).max_width(width).draw_at(0, 0, &mut screen);
+        let lit: Vec<i32> = (0..width)
+            .filter(|&x| (0..line_height()).any(|y| screen.get_pixel(x, y)))
+            .collect();
+        assert!(!lit.is_empty());
+        assert!(lit[0] >= width - 2 * char_width());
+    }
 }
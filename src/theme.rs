@@ -0,0 +1,184 @@
+use crate::font::Font;
+use crate::screen::Screen;
+use crate::shape::{to_pixel, Drawable, Line, Rect};
+
+/// Height and width of a checkbox or radio button, and height of a switch
+pub const INDICATOR_SIZE: i32 = 7;
+pub const SWITCH_WIDTH: i32 = 13;
+
+/// How the selected entry of a list or keyboard stands out
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SelectionStyle {
+    /// Light the whole entry and draw its contents unlit
+    Invert,
+    /// Draw a line along the bottom of the entry
+    Underline,
+    /// Draw square brackets down either side of the entry
+    Bracket,
+}
+
+/// How views draw the frame around the screen
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BorderStyle {
+    None,
+    Line,
+    /// A line with its corner pixels left out
+    Rounded,
+}
+
+/// The look shared by all views: how selection and borders are drawn, the spacing
+/// of rows and text, and whether the whole screen is shown dark on light.
+/// Set it with `GUI::set_theme`; views read it back from `Screen::theme`.
+#[derive(Clone)]
+pub struct Theme {
+    pub selection: SelectionStyle,
+    pub border: BorderStyle,
+    /// Space between the edge of an entry or the screen and the text inside it
+    pub padding: i32,
+    pub row_height: i32,
+    pub font: Font,
+    /// For secondary text, such as the second line of a menu entry
+    pub small_font: Font,
+    /// Show dark pixels on a lit background. Applies to everything drawn.
+    pub inverted: bool,
+}
+
+impl Theme {
+    pub fn new() -> Theme {
+        Theme {
+            selection: SelectionStyle::Invert,
+            border: BorderStyle::Line,
+            padding: 3,
+            row_height: 13,
+            font: Font::default(),
+            small_font: Font::Tiny4x6,
+            inverted: false,
+        }
+    }
+
+    /// The default theme, drawn dark on light
+    pub fn dark_on_light() -> Theme { Theme::new().inverted(true) }
+
+    pub fn selection(mut self, selection: SelectionStyle) -> Theme { self.selection = selection; self }
+    pub fn border(mut self, border: BorderStyle) -> Theme { self.border = border; self }
+    pub fn padding(mut self, padding: i32) -> Theme { self.padding = padding; self }
+    pub fn row_height(mut self, row_height: i32) -> Theme { self.row_height = row_height; self }
+    pub fn font(mut self, font: Font) -> Theme { self.font = font; self }
+    pub fn small_font(mut self, font: Font) -> Theme { self.small_font = font; self }
+    pub fn inverted(mut self, inverted: bool) -> Theme { self.inverted = inverted; self }
+
+    /// Whether the contents of an entry should be drawn unlit
+    pub fn inverts(&self, selected: bool) -> bool {
+        selected && self.selection == SelectionStyle::Invert
+    }
+
+    /// Fill and stroke for text inside an entry
+    pub fn text_colors(&self, selected: bool) -> (Option<u8>, Option<u8>) {
+        let inverted = self.inverts(selected);
+        (Some(inverted as u8), Some(!inverted as u8))
+    }
+
+    /// Mark the `width` x `height` entry at (x, y) as selected, in the theme's style.
+    /// Draws nothing when `selected` is false.
+    pub fn draw_selection(&self, x: i32, y: i32, width: i32, height: i32, selected: bool, screen: &mut Screen) {
+        if !selected {
+            return;
+        }
+        match self.selection {
+            SelectionStyle::Invert => {
+                Rect::new(width, height)
+                    .fill(Some(1))
+                    .at(x, y)
+                    .draw(screen);
+            }
+            SelectionStyle::Underline => {
+                Line::new(width - 2 * self.padding, 0)
+                    .at(x + self.padding, y + height - 2)
+                    .draw(screen);
+            }
+            SelectionStyle::Bracket => {
+                for &(edge, tick) in &[(x + 1, 1), (x + width - 1, -1)] {
+                    Line::new(0, height - 4)
+                        .at(edge, y + 2)
+                        .draw(screen);
+                    Line::new(tick, 0).at(edge, y + 2).draw(screen);
+                    Line::new(tick, 0).at(edge, y + height - 2).draw(screen);
+                }
+            }
+        }
+    }
+
+    /// Draw a checkbox at (x, y), inside an entry that may be selected
+    pub fn draw_check(&self, x: i32, y: i32, checked: bool, selected: bool, screen: &mut Screen) {
+        let (fill, stroke) = self.text_colors(selected);
+        Rect::new(INDICATOR_SIZE - 1, INDICATOR_SIZE - 1)
+            .at(x, y)
+            .stroke(stroke)
+            .fill(fill)
+            .draw(screen);
+        if checked {
+            Rect::new(INDICATOR_SIZE - 5, INDICATOR_SIZE - 5)
+                .at(x + 2, y + 2)
+                .stroke(stroke)
+                .fill(stroke)
+                .draw(screen);
+        }
+    }
+
+    /// Draw a radio button at (x, y): a checkbox with its corners cut off
+    pub fn draw_radio(&self, x: i32, y: i32, checked: bool, selected: bool, screen: &mut Screen) {
+        self.draw_check(x, y, checked, selected, screen);
+        self.round_corners(x, y, INDICATOR_SIZE, INDICATOR_SIZE, selected, screen);
+    }
+
+    /// Draw an on/off switch at (x, y), with its knob to the right and its track
+    /// lit when on
+    pub fn draw_switch(&self, x: i32, y: i32, on: bool, selected: bool, screen: &mut Screen) {
+        let (fill, stroke) = self.text_colors(selected);
+        let (track, knob, knob_x) = if on {
+            (stroke, fill, x + SWITCH_WIDTH - INDICATOR_SIZE + 2)
+        } else {
+            (fill, stroke, x + 2)
+        };
+        Rect::new(SWITCH_WIDTH - 1, INDICATOR_SIZE - 1)
+            .at(x, y)
+            .stroke(stroke)
+            .fill(track)
+            .draw(screen);
+        Rect::new(INDICATOR_SIZE - 5, INDICATOR_SIZE - 5)
+            .at(knob_x, y + 2)
+            .stroke(knob)
+            .fill(knob)
+            .draw(screen);
+        self.round_corners(x, y, SWITCH_WIDTH, INDICATOR_SIZE, selected, screen);
+    }
+
+    /// Clear the corner pixels of a `width` x `height` indicator
+    fn round_corners(&self, x: i32, y: i32, width: i32, height: i32, selected: bool, screen: &mut Screen) {
+        let background = self.inverts(selected) as u8;
+        let corners = [(x, y), (x + width - 1, y), (x, y + height - 1), (x + width - 1, y + height - 1)];
+        screen.draw(corners.iter().filter_map(|&(cx, cy)| to_pixel(cx, cy, background)));
+    }
+
+    /// Draw the frame around a `width` x `height` area at (x, y)
+    pub fn draw_border(&self, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        match self.border {
+            BorderStyle::None => {}
+            BorderStyle::Line => {
+                Rect::new(width, height)
+                    .at(x, y)
+                    .draw(screen);
+            }
+            BorderStyle::Rounded => {
+                Line::new(width - 2, 0).at(x + 1, y).draw(screen);
+                Line::new(width - 2, 0).at(x + 1, y + height).draw(screen);
+                Line::new(0, height - 2).at(x, y + 1).draw(screen);
+                Line::new(0, height - 2).at(x + width, y + 1).draw(screen);
+            }
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme { Theme::new() }
+}
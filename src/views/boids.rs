@@ -6,6 +6,7 @@ use na::{distance, Point2, Rotation2, Vector2};
 use nalgebra as na;
 use rand::Rng;
 use std::any::Any;
+use std::cell::Cell;
 use std::f32::consts::PI;
 
 const BOID_DISTANCE: f32 = 10.0;
@@ -130,7 +131,7 @@ impl Boid {
         );
     }
 
-    fn update(&mut self, boids: &Vec<Boid>, obstacles: &Vec<Obstacle>) {
+    fn update(&mut self, boids: &Vec<Boid>, obstacles: &Vec<Obstacle>, (width, height): (f32, f32)) {
         let nearby = self.nearby_boids(boids);
         if nearby.len() > 0 {
             self.separation(&nearby);
@@ -145,15 +146,15 @@ impl Boid {
         self.position += self.velocity;
         self.acceleration *= 0.3;
         if self.position.x < 0.0 {
-            self.position.x = 127.0;
+            self.position.x = width - 1.0;
         }
-        if self.position.x > 127.0 {
+        if self.position.x > width - 1.0 {
             self.position.x = 0.0;
         }
         if self.position.y < 0.0 {
-            self.position.y = 63.0;
+            self.position.y = height - 1.0;
         }
-        if self.position.y > 63.0 {
+        if self.position.y > height - 1.0 {
             self.position.y = 0.0;
         }
     }
@@ -175,25 +176,43 @@ impl ViewSpawner for BoidsViewBuilder {
 pub struct BoidsView {
     boids: Vec<Boid>,
     obstacles: Vec<Obstacle>,
+    size: (f32, f32),
+    // size of the screen as of the last render
+    screen_size: Cell<(f32, f32)>,
 }
 
 impl BoidsView {
     pub fn new() -> BoidsView {
         let mut rng = rand::thread_rng();
+        let (width, height) = (128.0, 64.0);
         BoidsView {
             boids: (0..20)
-                .map(|_| Boid::new(rng.gen::<f32>() * 128.0, rng.gen::<f32>() * 64.0))
+                .map(|_| Boid::new(rng.gen::<f32>() * width, rng.gen::<f32>() * height))
                 .collect(),
             obstacles: (0..3)
                 .map(|_| {
                     Obstacle::new(
-                        (rng.gen::<f32>() * 128.0) as i32,
-                        (rng.gen::<f32>() * 64.0) as i32,
+                        (rng.gen::<f32>() * width) as i32,
+                        (rng.gen::<f32>() * height) as i32,
                     )
                 })
                 .collect(),
+            size: (width, height),
+            screen_size: Cell::new((width, height)),
         }
     }
+
+    /// Stretch the flock and obstacles to fill a screen of a different size
+    fn resize(&mut self, (width, height): (f32, f32)) {
+        let (sx, sy) = (width / self.size.0, height / self.size.1);
+        for boid in &mut self.boids {
+            boid.position = Point2::new(boid.position.x * sx, boid.position.y * sy);
+        }
+        for obstacle in &mut self.obstacles {
+            obstacle.position = Point2::new(obstacle.position.x * sx, obstacle.position.y * sy);
+        }
+        self.size = (width, height);
+    }
 }
 
 impl View for BoidsView {
@@ -201,14 +220,19 @@ impl View for BoidsView {
         if buttons.b.was_pressed() {
             return Some(Back(None));
         }
+        if self.screen_size.get() != self.size {
+            self.resize(self.screen_size.get());
+        }
         let last_boids = self.boids.clone();
         for boid in &mut self.boids {
-            boid.update(&last_boids, &self.obstacles);
+            boid.update(&last_boids, &self.obstacles, self.size);
         }
         None
     }
 
     fn render(&self, screen: &mut Screen) {
+        self.screen_size.set((screen.get_width() as f32, screen.get_height() as f32));
+
         // draw boundary rectangle
 
         self.boids.iter().for_each(|boid| boid.draw(screen));
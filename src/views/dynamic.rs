@@ -0,0 +1,145 @@
+use std::any::Any;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::view::{UpdateResult, View};
+use crate::views::{Menu, MenuItem};
+
+const LOADING: &str = "Loading…";
+const EMPTY: &str = "Nothing here";
+
+/// Asks a `DynamicMenu` to fetch its entries again, from anywhere
+#[derive(Clone)]
+pub struct Refresher(Sender<()>);
+
+impl Refresher {
+    pub fn refresh(&self) {
+        // the menu has been closed if nothing is listening
+        let _ = self.0.send(());
+    }
+}
+
+/// A menu whose entries come from a data source, such as the networks in range or
+/// the files in a folder.
+///
+/// `fetch` runs on its own thread so slow sources don't hold up the screen. It's
+/// called as soon as the menu is made, then again on every refresh. Each item it
+/// returns is turned into an entry by `build`, along with a key naming it, so the
+/// selection stays on the same item when the entries change. Entries fetched while
+/// one of their submenus is open wait until it's closed.
+pub struct DynamicMenu<T> {
+    menu: Menu,
+    keys: Vec<String>,
+    build: Box<dyn Fn(T) -> (String, MenuItem)>,
+    requests: Sender<()>,
+    results: Receiver<Vec<T>>,
+    // fetched while a submenu was open, shown once it's closed
+    pending: Option<Vec<T>>,
+    refresh_every: Option<Duration>,
+    refreshed: Instant,
+    empty: String,
+}
+
+impl<T: Send + 'static> DynamicMenu<T> {
+    pub fn new<F>(mut fetch: F, build: Box<dyn Fn(T) -> (String, MenuItem)>) -> DynamicMenu<T>
+    where
+        F: FnMut() -> Vec<T> + Send + 'static,
+    {
+        let (requests, pending) = mpsc::channel::<()>();
+        let (sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            // stops once the menu is dropped
+            while pending.recv().is_ok() {
+                // refreshes asked for during a slow fetch only need one more
+                while pending.try_recv().is_ok() {}
+                if sender.send(fetch()).is_err() {
+                    break;
+                }
+            }
+        });
+        requests.send(()).expect("Could not start fetching menu entries");
+
+        let mut menu = Menu::new();
+        menu.add_entry(placeholder(LOADING));
+        DynamicMenu {
+            menu,
+            keys: Vec::new(),
+            build,
+            requests,
+            results,
+            pending: None,
+            refresh_every: None,
+            refreshed: Instant::now(),
+            empty: EMPTY.to_owned(),
+        }
+    }
+
+    pub fn title(mut self, title: String) -> Self {
+        self.menu = self.menu.title(title);
+        self
+    }
+
+    /// Fetch the entries again every `period`
+    pub fn refresh_every(mut self, period: Duration) -> Self {
+        self.refresh_every = Some(period);
+        self
+    }
+
+    /// Shown in place of the entries when the source has none
+    pub fn empty_text(mut self, text: String) -> Self {
+        self.empty = text;
+        self
+    }
+
+    pub fn refresh(&mut self) {
+        self.refreshed = Instant::now();
+        let _ = self.requests.send(());
+    }
+
+    pub fn refresher(&self) -> Refresher {
+        Refresher(self.requests.clone())
+    }
+
+    fn show(&mut self, items: Vec<T>) {
+        let selected_key = self.keys.get(self.menu.selected()).cloned();
+        let (keys, mut entries): (Vec<String>, Vec<MenuItem>) = items.into_iter().map(&self.build).unzip();
+        if entries.is_empty() {
+            entries.push(placeholder(&self.empty));
+        }
+        let index = selected_key
+            .and_then(|key| keys.iter().position(|k| *k == key))
+            .unwrap_or_else(|| self.menu.selected());
+        self.keys = keys;
+        self.menu.set_entries(entries);
+        self.menu.select(index);
+    }
+}
+
+fn placeholder(text: &str) -> MenuItem {
+    MenuItem::TextToFunc(text.to_owned(), Box::new(|| {}))
+}
+
+impl<T: Send + 'static> View for DynamicMenu<T> {
+    fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if let Some(period) = self.refresh_every {
+            if self.refreshed.elapsed() >= period {
+                self.refresh();
+            }
+        }
+        // only the newest entries matter if several fetches finished at once
+        if let Some(items) = self.results.try_iter().last() {
+            self.pending = Some(items);
+        }
+        if !self.menu.in_submenu() {
+            if let Some(items) = self.pending.take() {
+                self.show(items);
+            }
+        }
+        self.menu.update(buttons)
+    }
+    fn render(&self, screen: &mut Screen) { self.menu.render(screen) }
+    fn result(&mut self, result: Box<dyn Any>) { self.menu.result(result) }
+}
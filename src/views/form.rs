@@ -0,0 +1,62 @@
+use std::any::Any;
+
+use crate::view::{View, UpdateResult};
+use crate::screen::Screen;
+use crate::buttons::ButtonSet;
+use crate::shape::Drawable;
+use crate::layout::Space;
+use crate::widget::{FocusManager, Widget};
+
+/// A screen of widgets: add each one, place what `add` gives back in a layout,
+/// then hand the layout to `layout`. B leaves the form unless the focused widget
+/// uses it.
+pub struct Form {
+    focus: FocusManager,
+    root: Box<dyn Drawable>,
+}
+
+impl Default for Form {
+    fn default() -> Form { Form::new() }
+}
+
+impl Form {
+    pub fn new() -> Form {
+        Form {
+            focus: FocusManager::new(),
+            root: Box::new(Space::new(0, 0)),
+        }
+    }
+
+    /// Take charge of a widget, giving back what to place in the layout to show it
+    pub fn add(&mut self, widget: Box<dyn Widget>) -> Box<dyn Drawable> {
+        self.focus.add(widget)
+    }
+
+    /// Show this layout of the widgets, filling the screen
+    pub fn layout(&mut self, root: Box<dyn Drawable>) {
+        self.root = root;
+    }
+
+    /// Move focus to the widget added `index`th
+    pub fn focus(&mut self, index: usize) {
+        self.focus.focus(index);
+    }
+}
+
+impl View for Form {
+    fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        match self.focus.update(buttons) {
+            Some(result) => Some(result),
+            None if buttons.b.was_pressed() => Some(UpdateResult::Back(None)),
+            None => None,
+        }
+    }
+    fn render(&self, screen: &mut Screen) {
+        let (width, height) = (screen.get_width(), screen.get_height());
+        self.focus.render(self.root.as_ref(), 0, 0, width, height, screen);
+        screen.theme().clone().draw_border(0, 0, width - 1, height - 1, screen);
+    }
+    fn result(&mut self, result: Box<dyn Any>) {
+        self.focus.result(result);
+    }
+}
@@ -3,7 +3,8 @@ use std::any::Any;
 use crate::view::{View, UpdateResult};
 use crate::screen::Screen;
 use crate::buttons::ButtonSet;
-use crate::shape::{Drawable,Text,Section};
+use crate::shape::{Drawable,Text};
+use crate::layout::{Column, Size};
 
 pub struct HelloWorld {
     root: Box<dyn Drawable>
@@ -11,11 +12,12 @@ pub struct HelloWorld {
 
 impl HelloWorld {
     pub fn new(screen: &Screen) -> HelloWorld { 
-        let mut main = Section::new(screen.get_width() - 1, screen.get_height() - 1)
-            .stroke(Some(1))
-            .at(0, 0);
-        let text = Text::new("Hello, world!".to_owned()).at(3, 3);
-        main.push(Box::new(text));
+        let theme = screen.theme();
+        let text = Text::new("Hello, world!".to_owned())
+            .font(theme.font.clone());
+        let main = Column::new()
+            .padding(theme.padding)
+            .child(Box::new(text), Size::Fit);
         HelloWorld {
             root: Box::new(main)
         }
@@ -25,7 +27,9 @@ impl HelloWorld {
 impl View for HelloWorld {
     fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> { None }
     fn render(&self, screen: &mut Screen) {
-        self.root.draw(screen);
+        self.root.draw_in(0, 0, screen.get_width(), screen.get_height(), screen);
+        screen.theme().clone()
+            .draw_border(0, 0, screen.get_width() - 1, screen.get_height() - 1, screen);
     }
     fn result(&mut self,  result: Box<dyn Any>) {}
 }
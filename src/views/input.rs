@@ -1,9 +1,12 @@
 use std::any::Any;
+use std::time::Duration;
 
 use crate::view::{View, ViewSpawner, UpdateResult, ViewResult};
 use crate::screen::Screen;
+use crate::theme::Theme;
 use crate::buttons::{ButtonSet};
-use crate::shape::{Drawable,Text,Rect, Bitmap, Line};
+use crate::shape::{Drawable,Text,Rect, Bitmap, Line, AnimatedBitmap, SpriteSheet};
+use crate::layout::{Alignment, Clip, Column, Grid, Row, Size, Space};
 
 pub enum Key {
     Letter(char),
@@ -27,7 +30,8 @@ pub struct TextInput {
     x: i32,
     y: i32,
     shifting: bool,
-    string: String
+    string: String,
+    cursor: AnimatedBitmap
 }
 
 impl TextInput {
@@ -47,9 +51,13 @@ impl TextInput {
             ],
             x: 0, y: 0,
             shifting: false,
-            string: "".to_owned()
+            string: "".to_owned(),
+            cursor: AnimatedBitmap::new(SpriteSheet::cursor(), Duration::from_millis(500))
         }
     }
+    /// Start with `text` already typed
+    pub fn text(mut self, text: String) -> TextInput { self.string = text; self }
+
     pub fn spawner() -> TextInputSpawner {
         TextInputSpawner {  }
     }
@@ -86,7 +94,17 @@ const SPACE_ICON: &[u8] = &[
     0b11111100,
 ];
 
-const KEYBOARD_OFFSET: i32 = 15;
+// space around the text being typed
+const TEXT_MARGIN: i32 = 2;
+// gap between the text box, margin included, and the keyboard
+const KEYBOARD_GAP: i32 = 1;
+const KEY_WIDTH: i32 = 10;
+const KEY_HEIGHT: i32 = 12;
+
+/// The first of `count` keys to show when only `visible` fit, keeping `selected` near the middle
+fn first_visible(selected: i32, count: i32, visible: i32) -> i32 {
+    (selected - visible / 2).min(count - visible).max(0)
+}
 
 pub struct TextResult {
     text: String
@@ -98,6 +116,46 @@ impl ViewResult for TextResult {
     }
 }
 
+/// One key of the keyboard: its outline, whether it is selected, and its label
+struct KeyCap {
+    label: Box<dyn Drawable>,
+    selected: bool,
+}
+
+impl KeyCap {
+    fn new(key: &Key, selected: bool, theme: &Theme) -> KeyCap {
+        let icon = |image| Bitmap::new(image, 6, 8)
+            .at(2, 2)
+            .invert(theme.inverts(selected));
+        let (fill, stroke) = theme.text_colors(selected);
+        let label: Box<dyn Drawable> = match key {
+            Key::Submit => Box::new(icon(SUBMIT_ICON)),
+            Key::Cancel => Box::new(icon(CANCEL_ICON)),
+            Key::Letter(' ') => Box::new(icon(SPACE_ICON)),
+            Key::Letter(letter) => Box::new(Text::new(letter.to_string())
+                                    .at(3, 3)
+                                    .stroke(stroke)
+                                    .fill(fill)),
+        };
+        KeyCap { label, selected }
+    }
+}
+
+impl Drawable for KeyCap {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(0, 0, screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        Rect::new(KEY_WIDTH, KEY_HEIGHT)
+            .at(x, y)
+            .draw(screen);
+        screen.theme().clone().draw_selection(x, y, KEY_WIDTH, KEY_HEIGHT, self.selected, screen);
+        self.label.draw_at(x + self.label.get_x(), y + self.label.get_y(), screen);
+    }
+    fn get_x(&self) -> i32 { 0 }
+    fn get_y(&self) -> i32 { 0 }
+    fn get_width(&self) -> i32 { KEY_WIDTH }
+    fn get_height(&self) -> i32 { KEY_HEIGHT }
+}
+
 impl View for TextInput {
     fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
         let map = match self.shifting {
@@ -134,6 +192,7 @@ impl View for TextInput {
                 Key::Cancel => Some(UpdateResult::Back(None)),
                 Key::Letter(letter) => {
                     self.string.push(*letter);
+                    self.cursor.restart();
                     return None;
                 }
             }
@@ -146,38 +205,46 @@ impl View for TextInput {
             false => &self.normal,
             true => &self.shift
         };
-        for y in 0..map.len() as i32 {
-            for x in 0..map[y as usize].len() as i32 {
-                Rect::new(10, 12)
-                    .at(10 * x, KEYBOARD_OFFSET + 12 * y)
-                    .fill(Some((x == self.x && y == self.y) as u8))
-                    .stroke(Some(1))
-                    .draw(screen);
-                match map[y as usize][x as usize] {
-                    Key::Submit => Bitmap::new(SUBMIT_ICON, 6, 8)
-                                    .at(10 * x + 2, KEYBOARD_OFFSET + 12 * y + 2)
-                                    .invert(x == self.x && y == self.y)
-                                    .draw(screen),
-                    Key::Cancel => Bitmap::new(CANCEL_ICON, 6, 8)
-                                    .at(10 * x + 2, KEYBOARD_OFFSET + 12 * y + 2)
-                                    .invert(x == self.x && y == self.y)
-                                    .draw(screen),
-                    Key::Letter(' ') => Bitmap::new(SPACE_ICON, 6, 8)
-                                    .at(10 * x + 2, KEYBOARD_OFFSET + 12 * y + 2)
-                                    .invert(x == self.x && y == self.y)
-                                    .draw(screen),
-                    Key::Letter(letter) => Text::new(letter.to_string())
-                                    .at(10 * x + 3, KEYBOARD_OFFSET + 12 * y + 3)
-                                    .stroke(Some(!(x == self.x && y == self.y) as u8))
-                                    .fill(Some((x == self.x && y == self.y) as u8))
-                                    .draw(screen)
+        let theme = screen.theme().clone();
+        let mut typed = Row::new()
+            .child(Box::new(Text::new(self.string.clone()).font(theme.font.clone())), Size::Fit);
+        if let Some(cursor) = self.cursor.current() {
+            typed.push(Box::new(cursor.clone()), Size::Fit);
+        }
+        let text_box = Column::new()
+            .padding(TEXT_MARGIN)
+            .spacing(1)
+            .child(Box::new(typed), Size::Fixed(theme.font.bitmap().line_height()))
+            .child(Box::new(Line::new(screen.get_width() - 2 * TEXT_MARGIN, 0)), Size::Fit);
+        let keyboard_height = screen.get_height() - text_box.get_height() - KEYBOARD_GAP;
+
+        // scroll the keyboard when the screen is too small to show all of it,
+        // with one more key each way to show that there are more
+        let columns = map.iter().map(|row| row.len()).max().unwrap_or(0) as i32;
+        let visible_columns = ((screen.get_width() - 1) / KEY_WIDTH).max(1);
+        let visible_rows = ((keyboard_height - 1) / KEY_HEIGHT).max(1);
+        let first_x = first_visible(self.x, columns, visible_columns);
+        let first_y = first_visible(self.y, map.len() as i32, visible_rows);
+        let shown_columns = (visible_columns + 1).min(columns - first_x);
+        let mut keyboard = Grid::new(shown_columns as usize)
+            .column_width(Size::Fixed(KEY_WIDTH))
+            .row_height(Size::Fixed(KEY_HEIGHT));
+        for y in first_y..(first_y + visible_rows + 1).min(map.len() as i32) {
+            for x in first_x..first_x + shown_columns {
+                let selected = x == self.x && y == self.y;
+                match map[y as usize].get(x as usize) {
+                    Some(key) => keyboard.push(Box::new(KeyCap::new(key, selected, &theme))),
+                    None => keyboard.push(Box::new(Space::new(KEY_WIDTH, KEY_HEIGHT))),
                 }
             }
         }
-        Line::new(screen.get_width() - 4, 0)
-            .at(2, 11)
-            .draw(screen);
-        Text::new(self.string.clone()).at(2, 2).draw(screen);
+
+        Column::new()
+            .spacing(KEYBOARD_GAP)
+            .align(Alignment::Stretch)
+            .child(Box::new(text_box), Size::Fit)
+            .child(Box::new(Clip::new(Box::new(keyboard))), Size::Fill)
+            .draw_in(0, 0, screen.get_width(), screen.get_height(), screen);
     }
     fn result(&mut self, result: Box<dyn Any>) { }
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// How a remembering `Menu` orders its entries each time it's opened
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MenuOrder {
+    /// In the order they were added
+    AsAdded,
+    /// Most recently chosen first
+    Recent,
+    /// Most often chosen first
+    Frequent,
+}
+
+/// How often and when an entry was last chosen
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Usage {
+    count: u32,
+    // seconds since the Unix epoch
+    last: u64,
+}
+
+impl Usage {
+    /// Sort key for `order`, largest first
+    pub(crate) fn rank(&self, order: MenuOrder) -> (u64, u64) {
+        match order {
+            MenuOrder::Frequent => (self.count as u64, self.last),
+            _ => (self.last, self.count as u64),
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct MenuState {
+    selected: Option<String>,
+    #[serde(default)]
+    used: HashMap<String, Usage>,
+}
+
+/// The last entry selected in each menu, and how often and how recently each entry
+/// was chosen, kept in a small JSON file so it lasts across restarts. Entries are
+/// known by their labels. Clones share the same state.
+#[derive(Clone)]
+pub struct MenuMemory {
+    path: Rc<PathBuf>,
+    menus: Rc<RefCell<HashMap<String, MenuState>>>,
+}
+
+impl MenuMemory {
+    /// Read the state at `path`. A missing file starts empty, as does a broken one,
+    /// which is replaced on the next save.
+    pub fn open<P: AsRef<Path>>(path: P) -> MenuMemory {
+        let path = path.as_ref().to_path_buf();
+        let menus = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|err| {
+                eprintln!("Ignoring menu state in {}: {}", path.display(), err);
+                HashMap::new()
+            }),
+            Err(ref err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(err) => {
+                eprintln!("Could not read menu state from {}: {}", path.display(), err);
+                HashMap::new()
+            }
+        };
+        MenuMemory {
+            path: Rc::new(path),
+            menus: Rc::new(RefCell::new(menus)),
+        }
+    }
+
+    pub(crate) fn selected(&self, menu: &str) -> Option<String> {
+        self.menus.borrow().get(menu).and_then(|state| state.selected.clone())
+    }
+
+    pub(crate) fn usage(&self, menu: &str) -> HashMap<String, Usage> {
+        self.menus.borrow().get(menu).map(|state| state.used.clone()).unwrap_or_default()
+    }
+
+    /// Note `label` as the entry selected when `menu` was left
+    pub(crate) fn select(&self, menu: &str, label: &str) {
+        let mut menus = self.menus.borrow_mut();
+        let state = menus.entry(menu.to_owned()).or_default();
+        if state.selected.as_deref() != Some(label) {
+            state.selected = Some(label.to_owned());
+            drop(menus);
+            self.save();
+        }
+    }
+
+    /// Note `label` as chosen in `menu` just now
+    pub(crate) fn choose(&self, menu: &str, label: &str) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        {
+            let mut menus = self.menus.borrow_mut();
+            let state = menus.entry(menu.to_owned()).or_default();
+            state.selected = Some(label.to_owned());
+            let usage = state.used.entry(label.to_owned()).or_default();
+            usage.count += 1;
+            usage.last = now;
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(&*self.menus.borrow()).expect("Could not serialize menu state");
+        // written beside the old state and renamed over it, so a crash part way
+        // through can't leave a truncated file behind
+        let temp = self.path.with_extension("tmp");
+        if let Err(err) = fs::write(&temp, json).and_then(|_| fs::rename(&temp, &*self.path)) {
+            eprintln!("Could not save menu state to {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_survives_a_save_and_reopen() {
+        let path = std::env::temp_dir().join(format!("tinygui-memory-{}.json", std::process::id()));
+        let memory = MenuMemory::open(&path);
+        memory.choose("statuses", "Lunch");
+        memory.choose("statuses", "Lunch");
+        memory.select("settings", "Contrast");
+
+        let reopened = MenuMemory::open(&path);
+        assert_eq!(reopened.selected("statuses").as_deref(), Some("Lunch"));
+        assert_eq!(reopened.selected("settings").as_deref(), Some("Contrast"));
+        assert_eq!(reopened.usage("statuses")["Lunch"].count, 2);
+        assert!(reopened.usage("settings").is_empty());
+        // the temporary file was renamed over the state, not left beside it
+        assert!(!path.with_extension("tmp").exists());
+        fs::remove_file(&path).expect("remove test state");
+    }
+}
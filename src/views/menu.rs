@@ -2,30 +2,37 @@ use crate::view::{View, ViewSpawner, UpdateResult, UpdateResult::*};
 use crate::screen::Screen;
 use crate::buttons::ButtonSet;
 use crate::shape::{Drawable,Text,Rect,Line,Bitmap};
+use crate::theme::{Theme, INDICATOR_SIZE, SWITCH_WIDTH};
+use crate::views::{MenuMemory, MenuOrder, NumberEntry, TextInput};
+use crate::views::number::POINTER;
 use std::any::Any;
-use std::cmp::max;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::cmp::{max, min, Reverse};
+use std::time::{Duration, Instant};
 
-const ENTRY_HEIGHT: i32 = 13;
+// how long a selected entry waits before scrolling its label, and how fast it scrolls
+const MARQUEE_DELAY: Duration = Duration::from_millis(1000);
+const MARQUEE_SPEED: f32 = 30.0;
+// space between the end of a scrolling label and the start of its repeat
+const MARQUEE_GAP: i32 = 24;
 
-const ARROW_UP: &[u8] = &[
-    0b00000000,
-    0b00100000,
-    0b01110000,
-    0b10101000,
-    0b00100000,
-    0b00100000,
-    0b00100000,
-    0b00000000,
-];
+// how long the list takes to glide to a new scroll position
+const SCROLL_TIME: Duration = Duration::from_millis(120);
+const SCROLLBAR_WIDTH: i32 = 3;
+
+// between the titles of a submenu's breadcrumb
+const SEPARATOR: &str = " › ";
 
-const ARROW_DOWN: &[u8] = &[
+// one pixel of margin all round, so the arrow's outline stays inside the bitmap
+const ARROW: &[u8] = &[
     0b00000000,
-    0b00100000,
-    0b00100000,
-    0b00100000,
-    0b10101000,
-    0b01110000,
-    0b00100000,
+    0b00010000,
+    0b00111000,
+    0b01010100,
+    0b00010000,
+    0b00010000,
+    0b00010000,
     0b00000000,
 ];
 
@@ -37,14 +44,172 @@ pub trait CustomMenuEntry {
 pub enum MenuItem {
     Custom(Box<dyn CustomMenuEntry>),
     TextToView(String, Box<dyn ViewSpawner>),
-    TextToFunc(String, Box<dyn Fn()>)
+    TextToFunc(String, Box<dyn Fn()>),
+    /// An on/off switch, flipped with A
+    Toggle(String, Rc<Cell<bool>>),
+    /// A checkbox, ticked and unticked with A
+    Checkbox(String, Rc<Cell<bool>>),
+    /// One choice of a group sharing the cell, which A sets to this entry's value
+    Radio(String, Rc<Cell<usize>>, usize),
+    /// A number shown between arrows, changed with left and right
+    Spinner(String, NumberEntry),
+    /// A number shown as a bar, changed with left and right
+    Slider(String, NumberEntry),
+    /// A titled list of entries, opened in place with A and left with B
+    Submenu(String, Vec<MenuItem>),
+    /// Another entry with an icon, value or second line, added with `icon`, `value` and `secondary`
+    Detailed(Box<MenuItem>, EntryDetails),
+}
+
+/// Extras drawn around the label of a text entry
+#[derive(Clone, Default)]
+pub struct EntryDetails {
+    icon: Option<Bitmap>,
+    value: Option<String>,
+    secondary: Option<String>,
+}
+
+impl MenuItem {
+    /// A `Radio` entry for each label, setting `selected` to the index of the one chosen
+    pub fn radio_group(labels: Vec<String>, selected: Rc<Cell<usize>>) -> Vec<MenuItem> {
+        labels.into_iter()
+            .enumerate()
+            .map(|(i, label)| MenuItem::Radio(label, selected.clone(), i))
+            .collect()
+    }
+
+    /// Show an icon, up to 8x8, before the label
+    pub fn icon(self, icon: Bitmap) -> MenuItem {
+        self.detail(|details| details.icon = Some(icon))
+    }
+
+    /// Show a value or hint, such as "On" or "12m", at the right of the entry
+    pub fn value(self, value: String) -> MenuItem {
+        self.detail(|details| details.value = Some(value))
+    }
+
+    /// Show a second line of smaller text under the label. Every entry in the same
+    /// menu is made tall enough for one.
+    pub fn secondary(self, text: String) -> MenuItem {
+        self.detail(|details| details.secondary = Some(text))
+    }
+
+    fn detail(self, change: impl FnOnce(&mut EntryDetails)) -> MenuItem {
+        let (item, mut details) = match self {
+            MenuItem::Detailed(item, details) => (item, details),
+            item => (Box::new(item), EntryDetails::default()),
+        };
+        change(&mut details);
+        MenuItem::Detailed(item, details)
+    }
+
+    /// The entry without its details
+    fn base(&self) -> &MenuItem {
+        match self {
+            MenuItem::Detailed(item, _) => item.base(),
+            item => item,
+        }
+    }
+
+    fn base_mut(&mut self) -> &mut MenuItem {
+        match self {
+            MenuItem::Detailed(item, _) => item.base_mut(),
+            item => item,
+        }
+    }
+
+    /// The text the entry is shown with, if it isn't custom drawn
+    fn label(&self) -> Option<&str> {
+        use MenuItem::*;
+        match self.base() {
+            TextToView(text, _) | TextToFunc(text, _) | Toggle(text, _) | Checkbox(text, _) | Radio(text, _, _)
+            | Spinner(text, _) | Slider(text, _) | Submenu(text, _) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn details(&self) -> Option<&EntryDetails> {
+        match self {
+            MenuItem::Detailed(_, details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// Width of the state shown at the right of the entry, if it has any
+    fn indicator_width(&self, theme: &Theme) -> i32 {
+        match self.base() {
+            MenuItem::Toggle(..) => SWITCH_WIDTH,
+            MenuItem::Checkbox(..) | MenuItem::Radio(..) => INDICATOR_SIZE,
+            MenuItem::Spinner(_, number) => number.spinner_width(theme),
+            MenuItem::Slider(_, number) => number.slider_width(),
+            MenuItem::Submenu(..) => 3,
+            _ => 0
+        }
+    }
+
+    /// Draw the entry's state at `x` in the row at `row_y`, `height` pixels tall
+    fn draw_indicator(&self, x: i32, row_y: i32, height: i32, selected: bool, theme: &Theme, screen: &mut Screen) {
+        let y = row_y + (height - INDICATOR_SIZE + 1) / 2;
+        match self.base() {
+            MenuItem::Toggle(_, on) => theme.draw_switch(x, y, on.get(), selected, screen),
+            MenuItem::Checkbox(_, checked) => theme.draw_check(x, y, checked.get(), selected, screen),
+            MenuItem::Radio(_, group, value) => theme.draw_radio(x, y, group.get() == *value, selected, screen),
+            MenuItem::Spinner(_, number) => number.draw_spinner(x, row_y, height, selected, theme, screen),
+            MenuItem::Slider(_, number) => number.draw_slider(x, row_y, height, selected, theme, screen),
+            MenuItem::Submenu(..) => {
+                Bitmap::new(POINTER, 3, 5)
+                    .flip_horizontal()
+                    .invert(theme.inverts(selected))
+                    .at(x, row_y + (height - 5 + 1) / 2)
+                    .draw(screen)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// How a `Menu` shows there are more entries than fit on screen
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScrollIndicator {
+    /// Arrows in the footer, or over the first and last rows without one
+    Arrows,
+    /// A bar down the right edge, its thumb showing the part of the list in view
+    Scrollbar,
+}
+
+// a submenu that has been opened, and where its parent was left
+struct Level {
+    index: usize,
+    selected: usize,
+    first_shown: usize,
+    filter: String,
 }
 
 pub struct Menu {
     entries: Vec<MenuItem>,
     title: String,
+    // the submenus opened, outermost first; `selected`, `first_shown` and `filter` are for the innermost
+    path: Vec<Level>,
+    // position of the selected entry among those shown
     selected: usize,
-    first_shown: usize
+    selected_at: Instant,
+    first_shown: usize,
+    // where the list was scrolled from, in rows, and when, for gliding to `first_shown`
+    scroll_from: f32,
+    scrolled_at: Instant,
+    footer: bool,
+    scroll_indicator: ScrollIndicator,
+    // only entries whose labels match are shown
+    filter: String,
+    // whether the filter is being typed, so the text that comes back is for it
+    filtering: bool,
+    // C was pressed; the filter opens once it's let go, unless B joins it for the record chord
+    filter_pending: bool,
+    // where the selection and use of entries is kept, and the name this menu is kept under
+    memory: Option<(MenuMemory, String)>,
+    order: MenuOrder,
+    // how many entries fit on the screen, as of the last render
+    visible_rows: Cell<usize>
 }
 
 impl Menu {
@@ -52,8 +217,20 @@ impl Menu {
         Menu {
             entries: vec![],
             title: String::new(),
+            path: vec![],
             selected: 0,
-            first_shown: 0
+            selected_at: Instant::now(),
+            first_shown: 0,
+            scroll_from: 0.0,
+            scrolled_at: Instant::now(),
+            footer: true,
+            scroll_indicator: ScrollIndicator::Arrows,
+            filter: String::new(),
+            filtering: false,
+            filter_pending: false,
+            memory: None,
+            order: MenuOrder::AsAdded,
+            visible_rows: Cell::new(4)
         }
     }
 
@@ -62,16 +239,494 @@ impl Menu {
         self
     }
 
+    /// Show the title bar along the bottom, where the screen has room for it
+    pub fn footer(mut self, footer: bool) -> Menu {
+        self.footer = footer;
+        self
+    }
+
+    pub fn scroll_indicator(mut self, indicator: ScrollIndicator) -> Menu {
+        self.scroll_indicator = indicator;
+        self
+    }
+
+    /// Keep the selected entry, and which entries are chosen, in `memory` under `name`.
+    /// Each time the menu or one of its submenus is opened, the entry last selected
+    /// there is selected again. Entries added later are ordered and selected the same way.
+    pub fn remember(mut self, memory: MenuMemory, name: String) -> Menu {
+        self.memory = Some((memory, name));
+        self.recall();
+        self
+    }
+
+    /// Order the entries by how they've been used; needs `remember`
+    pub fn order(mut self, order: MenuOrder) -> Menu {
+        self.order = order;
+        self.recall();
+        self
+    }
+
     pub fn add_entry(&mut self, item: MenuItem) {
         self.entries.push(item);
+        self.recall();
+    }
+
+    pub fn add_entries(&mut self, items: Vec<MenuItem>) {
+        self.entries.extend(items);
+        self.recall();
+    }
+
+    // Changing the top level entries closes any open submenu and clears the filter,
+    // and keeps the same entry selected where it's still there.
+
+    pub fn insert(&mut self, index: usize, item: MenuItem) {
+        self.close_submenus();
+        self.entries.insert(index, item);
+        if index <= self.selected && self.entries.len() > 1 {
+            self.selected += 1;
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> MenuItem {
+        self.close_submenus();
+        let item = self.entries.remove(index);
+        if index < self.selected {
+            self.selected -= 1;
+        }
+        self.select(self.selected);
+        item
+    }
+
+    /// Move the entry at `from` so it ends up at `to`
+    pub fn move_entry(&mut self, from: usize, to: usize) {
+        self.close_submenus();
+        let item = self.entries.remove(from);
+        self.entries.insert(to, item);
+        if self.selected == from {
+            self.selected = to;
+        } else if from < self.selected && self.selected <= to {
+            self.selected -= 1;
+        } else if to <= self.selected && self.selected < from {
+            self.selected += 1;
+        }
+        self.scroll_to_selected();
+    }
+
+    /// Replace every entry, keeping the selection at the same position
+    pub fn set_entries(&mut self, items: Vec<MenuItem>) {
+        self.close_submenus();
+        self.entries = items;
+        self.select(self.selected);
+    }
+
+    pub fn len(&self) -> usize { self.entries.len() }
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Whether a submenu is open
+    pub fn in_submenu(&self) -> bool { !self.path.is_empty() }
+
+    /// The index of the selected entry, within the open submenu if there is one
+    pub fn selected(&self) -> usize {
+        self.shown().get(self.selected).copied().unwrap_or(0)
+    }
+
+    /// Select the entry at `index`, or the last one if there aren't that many.
+    /// Clears the filter if it hides the entry.
+    pub fn select(&mut self, index: usize) {
+        let index = index.min(self.current().len().saturating_sub(1));
+        if !self.shown().contains(&index) {
+            self.set_filter(String::new());
+        }
+        let position = self.shown().iter().position(|&i| i == index).unwrap_or(0);
+        if position != self.selected {
+            self.selected_at = Instant::now();
+        }
+        self.selected = position;
+        self.scroll_to_selected();
+    }
+
+    /// Show only the entries of the open menu whose labels contain `filter`, or
+    /// failing that its letters in order, ignoring case. An empty filter shows them all.
+    pub fn set_filter(&mut self, filter: String) {
+        let index = self.shown().get(self.selected).copied();
+        self.filter = filter;
+        let shown = self.shown();
+        self.selected = index.and_then(|index| shown.iter().position(|&i| i == index)).unwrap_or(0);
+        self.selected_at = Instant::now();
+        self.scroll_to_selected();
+        // the list has changed under it, so don't glide
+        self.scroll_from = self.first_shown as f32;
+    }
+
+    fn close_submenus(&mut self) {
+        if let Some(top) = self.path.first() {
+            self.selected = top.selected;
+            self.filter = top.filter.clone();
+            self.jump_to(top.first_shown);
+            self.path.clear();
+        }
+        self.set_filter(String::new());
+    }
+
+    /// Indices into the open menu of the entries shown: those matching the filter,
+    /// whole matches first, or all of them
+    fn shown(&self) -> Vec<usize> {
+        let entries = self.current();
+        if self.filter.is_empty() {
+            return (0..entries.len()).collect();
+        }
+        let filter = self.filter.to_lowercase();
+        let labels: Vec<String> = entries.iter()
+            .map(|entry| entry.label().unwrap_or("").to_lowercase())
+            .collect();
+        let whole = (0..labels.len()).filter(|&i| labels[i].contains(&filter));
+        let scattered = (0..labels.len()).filter(|&i| !labels[i].contains(&filter) && contains_in_order(&labels[i], &filter));
+        whole.chain(scattered).collect()
+    }
+
+    /// Where left and right skip to from the selected entry: the start of the next run
+    /// of entries beginning with the same letter, or of this run or the one before.
+    /// Wraps around at either end, as up and down do.
+    fn letter_jump(&self, shown: &[usize], forward: bool) -> usize {
+        let entries = self.current();
+        let letter = |position: usize| initial(&entries[shown[position]]);
+        let run_start = |mut position: usize| {
+            while position > 0 && letter(position - 1) == letter(position) {
+                position -= 1;
+            }
+            position
+        };
+        if forward {
+            (self.selected + 1..shown.len())
+                .find(|&position| letter(position) != letter(self.selected))
+                .unwrap_or(0)
+        } else {
+            let start = run_start(self.selected);
+            if start < self.selected {
+                start
+            } else if start == 0 {
+                run_start(shown.len() - 1)
+            } else {
+                run_start(start - 1)
+            }
+        }
+    }
+
+    /// The entries of the submenu open, or the top level
+    fn current(&self) -> &Vec<MenuItem> {
+        let mut entries = &self.entries;
+        for level in &self.path {
+            if let MenuItem::Submenu(_, children) = entries[level.index].base() {
+                entries = children;
+            }
+        }
+        entries
+    }
+
+    /// Open the submenu at `index`
+    fn enter(&mut self, index: usize) {
+        let filter = std::mem::take(&mut self.filter);
+        self.path.push(Level { index, selected: self.selected, first_shown: self.first_shown, filter });
+        self.selected = 0;
+        self.jump_to(0);
+        self.selected_at = Instant::now();
+        self.recall();
+    }
+
+    /// Go back up to the parent menu, selecting the submenu just left
+    fn leave(&mut self) {
+        if let Some(level) = self.path.pop() {
+            self.selected = level.selected;
+            self.filter = level.filter;
+            self.jump_to(level.first_shown);
+            self.selected_at = Instant::now();
+        }
+    }
+
+    /// Titles from the top menu down to the open submenu, skipping empty ones
+    fn titles(&self) -> Vec<&str> {
+        let mut titles = vec![self.title.as_str()];
+        let mut entries = &self.entries;
+        for level in &self.path {
+            if let MenuItem::Submenu(title, children) = entries[level.index].base() {
+                titles.push(title.as_str());
+                entries = children;
+            }
+        }
+        titles.into_iter().filter(|title| !title.is_empty()).collect()
+    }
+
+    /// Handle B: clear the filter, or go up a level from a submenu, or out of the
+    /// menu from the top
+    fn back(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if !buttons.b.was_pressed() {
+            None
+        } else if !self.filter.is_empty() {
+            self.set_filter(String::new());
+            None
+        } else if self.path.is_empty() {
+            self.note_selection();
+            Some(Back(None))
+        } else {
+            self.note_selection();
+            self.leave();
+            None
+        }
+    }
+
+    /// Labels of the submenus open, outermost first, then of the selected entry
+    pub(crate) fn location(&self) -> Vec<String> {
+        let mut labels = vec![];
+        let mut entries = &self.entries;
+        for level in &self.path {
+            labels.extend(entries[level.index].label().map(str::to_owned));
+            if let MenuItem::Submenu(_, children) = entries[level.index].base() {
+                entries = children;
+            }
+        }
+        labels.extend(entries.get(self.selected()).and_then(MenuItem::label).map(str::to_owned));
+        labels
+    }
+
+    /// Open the submenus and select the entry named by a `location`, as far as they
+    /// can still be found
+    pub(crate) fn go_to(&mut self, location: &[String]) {
+        for (depth, label) in location.iter().enumerate() {
+            let index = match self.current().iter().position(|entry| entry.label() == Some(label.as_str())) {
+                Some(index) => index,
+                None => break,
+            };
+            self.select(index);
+            let is_submenu = matches!(self.current()[index].base(), MenuItem::Submenu(..));
+            if depth + 1 < location.len() && is_submenu {
+                self.enter(index);
+            } else {
+                break;
+            }
+        }
+        self.scroll_from = self.first_shown as f32;
+    }
+
+    /// The memory this menu is kept in, and the name the open submenu is kept under there
+    fn memory_name(&self) -> Option<(MenuMemory, String)> {
+        let (memory, name) = self.memory.as_ref()?;
+        let mut name = name.clone();
+        let mut entries = &self.entries;
+        for level in &self.path {
+            if let MenuItem::Submenu(title, children) = entries[level.index].base() {
+                name.push('/');
+                name.push_str(title);
+                entries = children;
+            }
+        }
+        Some((memory.clone(), name))
+    }
+
+    /// Order the open menu by use and select the entry selected when it was last left
+    fn recall(&mut self) {
+        let (memory, name) = match self.memory_name() {
+            Some(memory) => memory,
+            None => return,
+        };
+        if self.order != MenuOrder::AsAdded {
+            let usage = memory.usage(&name);
+            let order = self.order;
+            // only actions and views move, among the places they already hold, so
+            // settings and submenus stay put; those never chosen keep their places after the rest
+            let entries = current_mut(&mut self.entries, &self.path);
+            let movable: Vec<bool> = entries
+                .iter()
+                .map(|entry| matches!(entry.base(), MenuItem::TextToFunc(..) | MenuItem::TextToView(..)))
+                .collect();
+            let mut ranked: Vec<usize> = (0..entries.len()).filter(|&i| movable[i]).collect();
+            ranked.sort_by_key(|&i| {
+                Reverse(entries[i].label().and_then(|label| usage.get(label)).map(|usage| usage.rank(order)))
+            });
+            let mut ranked = ranked.into_iter();
+            let mut taken: Vec<Option<MenuItem>> = entries.drain(..).map(Some).collect();
+            for (i, &moves) in movable.iter().enumerate() {
+                let from = if moves { ranked.next().expect("as many ranked entries as movable ones") } else { i };
+                entries.push(taken[from].take().expect("each entry is placed once"));
+            }
+        }
+        if let Some(label) = memory.selected(&name) {
+            if let Some(index) = self.current().iter().position(|entry| entry.label() == Some(label.as_str())) {
+                self.select(index);
+            }
+        }
+        // appear in place rather than gliding there
+        self.scroll_from = self.first_shown as f32;
+    }
+
+    fn note_selection(&self) {
+        if let Some((memory, name)) = self.memory_name() {
+            if let Some(label) = self.current().get(self.selected()).and_then(MenuItem::label) {
+                memory.select(&name, label);
+            }
+        }
+    }
+
+    /// Height of the rows of the open menu, with room for a second line if any entry has one
+    fn row_height(&self, theme: &Theme) -> i32 {
+        let two_lines = self.current()
+            .iter()
+            .filter_map(MenuItem::details)
+            .any(|details| details.secondary.is_some());
+        if two_lines {
+            theme.row_height + theme.small_font.bitmap().line_height()
+        } else {
+            theme.row_height
+        }
+    }
+
+    /// Scroll so the selected entry is on screen, keeping one entry either side of it
+    /// in view where there is room
+    fn scroll_to_selected(&mut self) {
+        let from = self.scroll_position();
+        let previous = self.first_shown;
+        let rows = self.visible_rows.get();
+        let margin = if rows > 2 { 1 } else { 0 };
+        if self.selected + margin >= self.first_shown + rows {
+            self.first_shown = self.selected + margin + 1 - rows;
+        } else if self.selected < self.first_shown + margin {
+            self.first_shown = self.selected.saturating_sub(margin);
+        }
+        self.first_shown = self.first_shown.min(self.shown().len().saturating_sub(rows));
+
+        // glide over short distances, but jump when wrapping round or skipping to an end
+        if self.first_shown != previous {
+            let to = self.first_shown as f32;
+            self.scroll_from = if (to - from).abs() <= rows as f32 { from } else { to };
+            self.scrolled_at = Instant::now();
+        }
+    }
+
+    /// Scroll straight to `first_shown`, without gliding
+    fn jump_to(&mut self, first_shown: usize) {
+        self.first_shown = first_shown;
+        self.scroll_from = first_shown as f32;
+    }
+
+    /// How far down the list is scrolled right now, in rows, part way to
+    /// `first_shown` while gliding
+    fn scroll_position(&self) -> f32 {
+        let t = self.scrolled_at.elapsed().as_secs_f32() / SCROLL_TIME.as_secs_f32();
+        let to = self.first_shown as f32;
+        if t >= 1.0 {
+            to
+        } else {
+            // ease out, slowing into place
+            self.scroll_from + (to - self.scroll_from) * (1.0 - (1.0 - t) * (1.0 - t))
+        }
+    }
+}
+
+/// The lowercase first letter of an entry's label
+fn initial(item: &MenuItem) -> Option<char> {
+    item.label()
+        .and_then(|label| label.chars().next())
+        .and_then(|c| c.to_lowercase().next())
+}
+
+/// Whether `text` has every character of `pattern`, in order but not necessarily together
+fn contains_in_order(text: &str, pattern: &str) -> bool {
+    let mut chars = text.chars();
+    pattern.chars().all(|wanted| chars.any(|c| c == wanted))
+}
+
+/// The entries of the submenu at the end of `path`, for changing
+fn current_mut<'a>(entries: &'a mut Vec<MenuItem>, path: &[Level]) -> &'a mut Vec<MenuItem> {
+    let mut entries = entries;
+    for level in path {
+        entries = match entries[level.index].base_mut() {
+            MenuItem::Submenu(_, children) => children,
+            _ => unreachable!("menu path leads through an entry that isn't a submenu"),
+        };
+    }
+    entries
+}
+
+/// The path to the open menu, joined by `SEPARATOR`. Titles are dropped from the
+/// front, with an ellipsis marking the cut, until it fits `width`.
+fn breadcrumb(titles: &[&str], width: i32, theme: &Theme) -> String {
+    let measure = |text: &str| Text::new(text.to_owned()).font(theme.font.clone()).get_width();
+    let full = titles.join(SEPARATOR);
+    if measure(&full) <= width {
+        return full;
+    }
+    let ellipsis = theme.font.bitmap().ellipsis();
+    for start in 1..titles.len() {
+        let cut = format!("{}{}{}", ellipsis, SEPARATOR.trim_start(), titles[start..].join(SEPARATOR));
+        if measure(&cut) <= width {
+            return cut;
+        }
+    }
+    // even the innermost title is too long, so let it be cut off at the end instead
+    titles.last().map_or(String::new(), |title| title.to_string())
+}
+
+/// The number of rows `row_height` tall that fit on a screen `height` pixels tall,
+/// and the y coordinate of the footer if it's wanted and there is room for it.
+/// The footer is dropped on screens too short for it and two rows.
+fn layout(height: i32, row_height: i32, footer: bool, theme: &Theme) -> (usize, Option<i32>) {
+    let footer_y = height - footer_height(theme);
+    if footer && footer_y >= 2 * row_height {
+        ((footer_y / row_height) as usize, Some(footer_y))
+    } else {
+        (max(1, height / row_height) as usize, None)
+    }
+}
+
+/// Height of the title bar along the bottom
+fn footer_height(theme: &Theme) -> i32 {
+    theme.font.bitmap().line_height() + theme.padding + 1
+}
+
+/// How far a label `width` pixels wide has scrolled after being selected for `elapsed`.
+/// The label pauses, scrolls until its repeat reaches the start, then loops.
+fn marquee_offset(elapsed: Duration, width: i32) -> i32 {
+    let cycle = width + MARQUEE_GAP;
+    let period = MARQUEE_DELAY.as_secs_f32() + cycle as f32 / MARQUEE_SPEED;
+    let t = elapsed.as_secs_f32() % period - MARQUEE_DELAY.as_secs_f32();
+    if t <= 0.0 {
+        0
+    } else {
+        ((t * MARQUEE_SPEED) as i32).min(cycle)
     }
 }
 
 impl View for Menu {
     fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        // any text from the keyboard came back before now
+        self.filtering = false;
+        if buttons.c.was_pressed() {
+            self.filter_pending = true;
+        }
+        if buttons.b.is_down() {
+            self.filter_pending = false;
+        }
+        if self.filter_pending && !buttons.c.is_down() {
+            self.filter_pending = false;
+            self.filtering = true;
+            return Some(NewView(Box::new(TextInput::new().text(self.filter.clone()))));
+        }
+
+        let previous = self.selected;
+        let shown = self.shown();
+        let count = shown.len();
+        if count == 0 {
+            return self.back(buttons);
+        }
+
+        // numbers take left and right for themselves
+        match current_mut(&mut self.entries, &self.path).get_mut(shown[self.selected]).map(MenuItem::base_mut) {
+            Some(MenuItem::Spinner(_, number)) | Some(MenuItem::Slider(_, number)) => number.update(buttons),
+            _ => {}
+        }
+
         // navigating the menu
         if buttons.down.was_pressed() {
-            if self.selected == self.entries.len() - 1 {
+            if self.selected == count - 1 {
                 self.selected = 0;
             } else {
                 self.selected += 1;
@@ -80,132 +735,405 @@ impl View for Menu {
 
         if buttons.up.was_pressed() {
             if self.selected == 0 {
-                self.selected = self.entries.len() - 1;
+                self.selected = count - 1;
             } else {
                 self.selected -= 1;
             }
         }
 
-        // skipping to the start/end of the menu
+        // skipping by first letter
         if buttons.left.was_pressed() {
-            self.selected = 0;
+            self.selected = self.letter_jump(&shown, false);
         }
 
         if buttons.right.was_pressed() {
-            self.selected = self.entries.len() - 1;
+            self.selected = self.letter_jump(&shown, true);
         }
 
-        if self.selected == self.entries.len() - 1 {
-            self.first_shown = max(0, match self.selected.checked_sub(3) { Some(x) => x, None => 0 });
-        } else if self.selected == 0 {
-            self.first_shown = 0;
-        } else if self.selected >= self.first_shown + 2 {
-            while self.first_shown < self.selected - 2 {
-                self.first_shown += 1;
-            }
-        } else if self.selected <= self.first_shown {
-            while self.first_shown > self.selected - 1 {
-                self.first_shown -= 1;
-            }
+        self.scroll_to_selected();
+
+        // restart the marquee on the newly selected entry
+        if self.selected != previous {
+            self.selected_at = Instant::now();
         }
 
         // selecting options
         if buttons.a.was_pressed() {
             use MenuItem::*;
-            match &mut self.entries[self.selected] {
+            let index = shown[self.selected];
+            if let Some((memory, name)) = self.memory_name() {
+                if let Some(label) = self.current()[index].label() {
+                    memory.choose(&name, label);
+                }
+            }
+            match current_mut(&mut self.entries, &self.path)[index].base_mut() {
                 Custom(entry) => entry.activate(),
                 TextToView(_, spawner) => Some(NewView(spawner.spawn())),
                 TextToFunc(_, func) => {
                     func();
                     None
                 }
+                Toggle(_, state) | Checkbox(_, state) => {
+                    state.set(!state.get());
+                    None
+                }
+                Radio(_, group, value) => {
+                    group.set(*value);
+                    None
+                }
+                Spinner(..) | Slider(..) => None,
+                Submenu(..) => {
+                    self.enter(index);
+                    None
+                }
+                Detailed(..) => unreachable!("the details were taken off"),
             }
-        } else if buttons.b.was_pressed() {
-            Some(Back(None))
         } else {
-            None
+            self.back(buttons)
         }
     }
 
     fn render(&self, screen: &mut Screen) {
-        // draw entries
-        for (i, entry) in self.entries.iter().skip(self.first_shown).take(4).enumerate() {
+        let theme = screen.theme().clone();
+        let row_height = self.row_height(&theme);
+        let padding = theme.padding;
+        let (rows, footer_y) = layout(screen.get_height(), row_height, self.footer, &theme);
+        self.visible_rows.set(rows);
+        // offset of the label from the top of its row, centring it in the first line
+        let line_height = theme.font.bitmap().line_height();
+        let text_y = (theme.row_height - line_height + 1) / 2;
+
+        let entries = self.current();
+        let shown = self.shown();
+        let more_above = self.first_shown > 0;
+        let more_below = self.first_shown + rows < shown.len();
+        let scrollbar = self.scroll_indicator == ScrollIndicator::Scrollbar && (more_above || more_below);
+        let list_width = if scrollbar { screen.get_width() - SCROLLBAR_WIDTH - 1 } else { screen.get_width() };
+        // down to and including the bottom edge of the last whole row
+        let list_height = rows as i32 * row_height + 1;
+
+        // draw entries, offset by however far they are through gliding into place
+        let position = self.scroll_position();
+        screen.set_clip(0, 0, screen.get_width(), list_height);
+        for (i, &index) in shown.iter().enumerate().skip(position as usize).take(rows + 1) {
             use MenuItem::*;
-            let is_selected = std::ptr::eq(&self.entries[self.selected as usize], entry);
-            match entry {
+            let entry = &entries[index];
+            let is_selected = i == self.selected;
+            let row_y = ((i as f32 - position) * row_height as f32).round() as i32;
+            match entry.base() {
                 Custom(entry) => {
                     entry.render(
-                        (i as i32) * ENTRY_HEIGHT, 0,
-                        screen.get_width(), ENTRY_HEIGHT,
+                        0, row_y,
+                        list_width, row_height,
                         is_selected, screen
-                    )
+                    );
+                    screen.set_clip(0, 0, screen.get_width(), list_height);
                 }
-                TextToView(text, _) | TextToFunc(text, _) => {
-                    Rect::new(screen.get_width() - 1, ENTRY_HEIGHT)
-                        .fill(Some(is_selected as u8))
-                        .at(0, i as i32 * ENTRY_HEIGHT)
-                        .draw(screen);
-                    Text::new(text.clone())
-                        .at(3, i as i32 * ENTRY_HEIGHT + 3)
-                        .fill(Some(is_selected as u8))
-                        .stroke(Some(!is_selected as u8))
+                TextToView(text, _) | TextToFunc(text, _) | Toggle(text, _) | Checkbox(text, _) | Radio(text, _, _)
+                | Spinner(text, _) | Slider(text, _) | Submenu(text, _) => {
+                    let details = entry.details();
+                    Rect::new(list_width - 1, row_height)
+                        .at(0, row_y)
                         .draw(screen);
+                    theme.draw_selection(0, row_y, list_width - 1, row_height, is_selected, screen);
+                    let (fill, stroke) = theme.text_colors(is_selected);
+                    let label = Text::new(text.clone())
+                        .font(theme.font.clone())
+                        .emoji(true)
+                        .fill(fill)
+                        .stroke(stroke);
+                    let y = row_y + text_y;
+                    let width = label.get_width();
+                    let mut x = padding;
+                    let mut right = list_width - padding;
+                    if let Some(icon) = details.and_then(|details| details.icon.as_ref()) {
+                        icon.clone()
+                            .invert(theme.inverts(is_selected))
+                            .at(x, row_y + (row_height - icon.get_height() + 1) / 2)
+                            .draw(screen);
+                        x += icon.get_width() + padding;
+                    }
+                    let indicator = entry.indicator_width(&theme);
+                    if indicator > 0 {
+                        right -= indicator;
+                        entry.draw_indicator(right, row_y, row_height, is_selected, &theme, screen);
+                        right -= padding;
+                    }
+                    if let Some(value) = details.and_then(|details| details.value.as_ref()) {
+                        let value = Text::new(value.clone())
+                            .font(theme.font.clone())
+                            .emoji(true)
+                            .fill(fill)
+                            .stroke(stroke);
+                        right -= value.get_width();
+                        value.draw_at(right, y, screen);
+                        right -= padding;
+                    }
+                    let available = right - x;
+                    if is_selected && width > available {
+                        // scroll the label within the row, drawing its repeat behind it
+                        let offset = marquee_offset(self.selected_at.elapsed(), width);
+                        let top = max(row_y, 0);
+                        screen.set_clip(x, top, available, min(row_y + row_height, list_height) - top);
+                        label.draw_at(x - offset, y, screen);
+                        label.draw_at(x - offset + width + MARQUEE_GAP, y, screen);
+                        screen.set_clip(0, 0, screen.get_width(), list_height);
+                    } else {
+                        label.max_width(available)
+                            .ellipsis(true)
+                            .draw_at(x, y, screen);
+                    }
+                    if let Some(secondary) = details.and_then(|details| details.secondary.as_ref()) {
+                        Text::new(secondary.clone())
+                            .font(theme.small_font.clone())
+                            .fill(fill)
+                            .stroke(stroke)
+                            .max_width(available)
+                            .ellipsis(true)
+                            .draw_at(x, y + line_height + 1, screen);
+                    }
                 }
+                Detailed(..) => unreachable!("the details were taken off"),
             }
         }
-        // draw a line in case there are less than 4 options
-        Line::new(screen.get_width() - 1, 0)
-            .at(0, 13 * 4)
-            .draw(screen);
+        screen.clear_clip();
+        if shown.is_empty() && !self.filter.is_empty() {
+            Text::new("No matches".to_owned())
+                .font(theme.font.clone())
+                .at(padding, text_y)
+                .draw(screen);
+        }
 
-        if !self.title.is_empty() {
-            Text::new(self.title.clone())
-                .at(3, 4 * 13 + 3)
+        if scrollbar {
+            // a track down the middle, with a thumb over it as long as the share of entries in view
+            let x = list_width;
+            let track = list_height - 1;
+            let thumb = max(3, track * rows as i32 / shown.len() as i32).min(track);
+            // a filter or a refresh can leave the glide starting past the end of a shorter list
+            let overflow = shown.len().saturating_sub(rows).max(1);
+            let position = position.max(0.0).min(overflow as f32);
+            let thumb_y = (position * (track - thumb) as f32 / overflow as f32).round() as i32;
+            Line::new(0, track)
+                .at(x + SCROLLBAR_WIDTH / 2, 0)
                 .draw(screen);
-        } else {
-            for i in 0..15 {
-                for j in 0..4 {
-                    Line::new(9, 9)
-                        .at(i * 9 + j - 4, 13 * 4 + 1)
+            Rect::new(SCROLLBAR_WIDTH - 1, thumb)
+                .fill(Some(1))
+                .at(x, thumb_y)
+                .draw(screen);
+        }
+        let arrows = self.scroll_indicator == ScrollIndicator::Arrows;
+        match footer_y {
+            Some(footer_y) => {
+                // draw a line in case there are fewer entries than rows
+                Line::new(screen.get_width() - 1, 0)
+                    .at(0, footer_y)
+                    .draw(screen);
+
+                let quoted = format!("\"{}\"", self.filter);
+                let mut titles = self.titles();
+                if !self.filter.is_empty() {
+                    titles.push(&quoted);
+                }
+                if !titles.is_empty() {
+                    let width = screen.get_width() - 2 * padding;
+                    Text::new(breadcrumb(&titles, width, &theme))
+                        .font(theme.font.clone())
+                        .at(padding, footer_y + padding)
+                        .max_width(width)
+                        .ellipsis(true)
+                        .draw(screen);
+                } else {
+                    let stripe = footer_height(&theme) - 3;
+                    for i in 0..screen.get_width() / stripe + 2 {
+                        for j in 0..4 {
+                            Line::new(stripe, stripe)
+                                .at(i * stripe + j - 4, footer_y + 1)
+                                .draw(screen);
+                        }
+                    }
+                }
+
+                let arrow_y = footer_y + (footer_height(&theme) - 8) / 2;
+                if arrows && more_above {
+                    Bitmap::new(ARROW, 7, 8)
+                        .outlined()
+                        .at(2, arrow_y)
+                        .draw(screen);
+                }
+                if arrows && more_below {
+                    Bitmap::new(ARROW, 7, 8)
+                        .flip_vertical()
+                        .outlined()
+                        .at(screen.get_width() - 9, arrow_y)
+                        .draw(screen);
+                }
+            }
+            None => {
+                // no footer, so overlay the arrows on the right of the first and last rows
+                if arrows && more_above {
+                    Bitmap::new(ARROW, 7, 8)
+                        .outlined()
+                        .at(screen.get_width() - 9, 2)
+                        .draw(screen);
+                }
+                if arrows && more_below {
+                    Bitmap::new(ARROW, 7, 8)
+                        .flip_vertical()
+                        .outlined()
+                        .at(screen.get_width() - 9, rows as i32 * row_height - 10)
                         .draw(screen);
                 }
             }
         }
 
-        // draw up arrow
-        if self.first_shown > 0
-            && self.entries.len() > 4 {
-            // draw black rectangle as background
-            Rect::new(10, 11)
-                .fill(Some(0))
-                .at(0, 4 * 13)
-                .draw(screen);
+        theme.draw_border(0, 0, screen.get_width() - 1, screen.get_height() - 1, screen);
+    }
+    fn result(&mut self, result: Box<dyn Any>) {
+        if self.filtering {
+            if let Ok(filter) = result.downcast::<String>() {
+                self.set_filter(*filter);
+            }
+        }
+    }
+}
 
-            // draw actual arrow
-            Bitmap::new(ARROW_UP, 5, 8)
-                .at(3, 4 * 13 + 2)
-                .draw(screen);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn menu(labels: &[&str]) -> Menu {
+        let mut menu = Menu::new();
+        for label in labels {
+            menu.add_entry(MenuItem::TextToFunc(label.to_string(), Box::new(|| {})));
         }
+        menu
+    }
 
-        // draw down arrow
-        if self.entries.len() > 4 && self.first_shown < self.entries.len() - 4 {
-            // draw black rectangle as background
-            Rect::new(10, 11)
-                .fill(Some(0))
-                .at(screen.get_width() - 11, 4 * 13)
-                .draw(screen);
+    fn press(menu: &mut Menu, button: fn(&mut ButtonSet) -> &mut crate::buttons::Button) -> usize {
+        let mut buttons = ButtonSet::virtual_buttons();
+        button(&mut buttons).press();
+        menu.update(&mut buttons);
+        menu.selected()
+    }
 
-            // draw actual arrow
-            Bitmap::new(ARROW_DOWN, 5, 8)
-                .at(screen.get_width() - 8, 4 * 13 + 2)
-                .draw(screen);
+    const STATUSES: &[&str] = &["Away", "Commuting", "In a meeting", "Lunch", "Out sick", "Working remotely"];
+
+    #[test]
+    fn filter_matches_substrings_ignoring_case() {
+        let mut menu = menu(STATUSES);
+        menu.set_filter("ING".to_owned());
+        assert_eq!(menu.shown(), [1, 2, 5]);
+    }
+
+    #[test]
+    fn filter_matches_letters_in_order_after_substrings() {
+        let mut menu = menu(STATUSES);
+        menu.set_filter("wrm".to_owned());
+        assert_eq!(menu.shown(), [5]);
+        menu.set_filter("ot".to_owned());
+        assert_eq!(menu.shown(), [5, 1, 4]);
+        menu.set_filter("xyz".to_owned());
+        assert!(menu.shown().is_empty());
+    }
+
+    #[test]
+    fn clearing_the_filter_keeps_the_selection() {
+        let mut menu = menu(STATUSES);
+        menu.set_filter("ot".to_owned());
+        assert_eq!(press(&mut menu, |b| &mut b.down), 1);
+        // B clears the filter rather than leaving
+        assert_eq!(press(&mut menu, |b| &mut b.b), 1);
+        assert_eq!(menu.shown().len(), STATUSES.len());
+    }
+
+    #[test]
+    fn right_jumps_to_the_next_letter_and_wraps() {
+        let mut menu = menu(&["Away", "Busy", "Bored", "Coffee", "Cycling", "Lunch"]);
+        assert_eq!(press(&mut menu, |b| &mut b.right), 1);
+        assert_eq!(press(&mut menu, |b| &mut b.right), 3);
+        assert_eq!(press(&mut menu, |b| &mut b.right), 5);
+        assert_eq!(press(&mut menu, |b| &mut b.right), 0);
+    }
+
+    #[test]
+    fn left_jumps_to_the_start_of_this_letter_or_the_last_and_wraps() {
+        let mut menu = menu(&["Away", "Busy", "Bored", "Coffee", "Cycling", "Lunch"]);
+        assert_eq!(press(&mut menu, |b| &mut b.left), 5);
+        assert_eq!(press(&mut menu, |b| &mut b.left), 3);
+        menu.select(4);
+        assert_eq!(press(&mut menu, |b| &mut b.left), 3);
+        assert_eq!(press(&mut menu, |b| &mut b.left), 1);
+    }
+
+    fn measure(text: &str, theme: &Theme) -> i32 {
+        Text::new(text.to_owned()).font(theme.font.clone()).get_width()
+    }
+
+    #[test]
+    fn breadcrumb_fits_whole_when_it_can() {
+        let theme = Theme::default();
+        assert_eq!(breadcrumb(&["Main", "Slack"], 128, &theme), "Main › Slack");
+    }
+
+    #[test]
+    fn breadcrumb_drops_titles_from_the_left() {
+        let theme = Theme::default();
+        let titles = ["Main", "Settings", "Slack", "Custom statuses", "Work"];
+        let width = measure("Main › Settings › Slack › Custom", &theme);
+        let crumb = breadcrumb(&titles, width, &theme);
+        assert!(crumb.starts_with(theme.font.bitmap().ellipsis()), "{}", crumb);
+        assert!(crumb.ends_with(" › Work"), "{}", crumb);
+        assert!(measure(&crumb, &theme) <= width);
+        assert!(!crumb.contains("Main"));
+    }
+
+    #[test]
+    fn breadcrumb_keeps_an_overlong_last_title() {
+        let theme = Theme::default();
+        assert_eq!(breadcrumb(&["Main", "A very long title"], 20, &theme), "A very long title");
+    }
+
+    /// A menu remembering usage of `b` (chosen once, recently) and `d` (chosen often,
+    /// a while ago), with a toggle that keeps its place
+    fn ordered(order: MenuOrder, name: &str) -> Vec<String> {
+        let path = std::env::temp_dir().join(format!("tinygui-order-{}-{}.json", name, std::process::id()));
+        fs::write(&path, r#"{ "m": { "selected": "a", "used": {
+            "b": { "count": 1, "last": 200 },
+            "d": { "count": 5, "last": 100 }
+        } } }"#).expect("write test state");
+        let mut menu = Menu::new().remember(MenuMemory::open(&path), "m".to_owned()).order(order);
+        for label in &["a", "b", "c", "d", "e"] {
+            menu.add_entry(MenuItem::TextToFunc(label.to_string(), Box::new(|| {})));
         }
+        menu.entries.insert(2, MenuItem::Toggle("t".to_owned(), Rc::new(Cell::new(false))));
+        menu.recall();
+        fs::remove_file(&path).expect("remove test state");
+        menu.entries.iter().filter_map(MenuItem::label).map(str::to_owned).collect()
+    }
 
-        // draw boundary rectangle
-        Rect::new(screen.get_width() - 1, screen.get_height() - 1)
-            .at(0, 0)
-            .draw(screen);
+    #[test]
+    fn recent_order_puts_the_latest_first_and_unused_after_in_place() {
+        assert_eq!(ordered(MenuOrder::Recent, "recent"), ["b", "d", "t", "a", "c", "e"]);
+    }
+
+    #[test]
+    fn frequent_order_puts_the_most_chosen_first_and_unused_after_in_place() {
+        assert_eq!(ordered(MenuOrder::Frequent, "frequent"), ["d", "b", "t", "a", "c", "e"]);
     }
-    fn result(&mut self,  result: Box<dyn Any>) {}
-}
 
+    #[test]
+    fn added_order_is_left_alone() {
+        assert_eq!(ordered(MenuOrder::AsAdded, "added"), ["a", "b", "t", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn letter_jumps_stay_within_the_filter() {
+        let mut menu = menu(STATUSES);
+        menu.set_filter("ing".to_owned());
+        assert_eq!(press(&mut menu, |b| &mut b.right), 2);
+        assert_eq!(press(&mut menu, |b| &mut b.right), 5);
+        assert_eq!(press(&mut menu, |b| &mut b.right), 1);
+    }
+}
@@ -0,0 +1,327 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Deserialize;
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::view::{UpdateResult, View, ViewSpawner};
+use crate::views::{Menu, MenuItem, NumberEntry};
+
+// how often to look for changes to the file
+const RELOAD_CHECK: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub enum MenuFileError {
+    Read(String),
+    Parse(String),
+    /// The file names an action, view or value nothing was registered under
+    Unregistered(String),
+    /// An entry's settings don't make sense, such as a spinner with no range
+    Invalid(String),
+}
+
+impl fmt::Display for MenuFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenuFileError::Read(msg) => write!(f, "could not read menu file: {}", msg),
+            MenuFileError::Parse(msg) => write!(f, "invalid menu file: {}", msg),
+            MenuFileError::Unregistered(msg) => write!(f, "menu file refers to {}", msg),
+            MenuFileError::Invalid(msg) => write!(f, "invalid menu entry: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MenuFileError {}
+
+/// A menu tree, usually read from a JSON file such as
+///
+/// ```json
+/// {
+///     "title": "Main",
+///     "entries": [
+///         { "type": "action", "label": "Take Photo", "action": "take_photo" },
+///         { "type": "view", "label": "Boids", "view": "boids" },
+///         { "type": "submenu", "label": "Settings", "entries": [
+///             { "type": "toggle", "label": "Wifi", "key": "wifi" },
+///             { "type": "spinner", "label": "Contrast", "key": "contrast", "min": 0, "max": 255 },
+///             { "type": "radio", "key": "speed", "options": ["Slow", "Fast"] }
+///         ] },
+///         { "type": "items", "name": "statuses" }
+///     ]
+/// }
+/// ```
+///
+/// Actions, views, values and generated items are looked up by name in a `MenuRegistry`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct MenuDefinition {
+    #[serde(default)]
+    pub title: String,
+    pub entries: Vec<EntryDefinition>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum EntryDefinition {
+    Action { label: String, action: String },
+    View { label: String, view: String },
+    Submenu { label: String, entries: Vec<EntryDefinition> },
+    Toggle { label: String, key: String },
+    Checkbox { label: String, key: String },
+    /// One entry for each option, choosing its index
+    Radio { key: String, options: Vec<String> },
+    Spinner {
+        label: String,
+        key: String,
+        min: f32,
+        max: f32,
+        #[serde(default = "default_step")]
+        step: f32,
+        #[serde(default)]
+        decimals: usize,
+    },
+    Slider {
+        label: String,
+        key: String,
+        min: f32,
+        max: f32,
+        #[serde(default = "default_step")]
+        step: f32,
+    },
+    /// Entries made by the program, such as a list read from elsewhere
+    Items { name: String },
+}
+
+fn default_step() -> f32 { 1.0 }
+
+/// What the names in a menu file refer to
+pub struct MenuRegistry {
+    actions: HashMap<String, Rc<dyn Fn()>>,
+    views: HashMap<String, Rc<dyn ViewSpawner>>,
+    flags: HashMap<String, Rc<Cell<bool>>>,
+    choices: HashMap<String, Rc<Cell<usize>>>,
+    numbers: HashMap<String, Rc<Cell<f32>>>,
+    items: HashMap<String, Rc<dyn Fn() -> Vec<MenuItem>>>,
+}
+
+impl Default for MenuRegistry {
+    fn default() -> MenuRegistry { MenuRegistry::new() }
+}
+
+impl MenuRegistry {
+    pub fn new() -> MenuRegistry {
+        MenuRegistry {
+            actions: HashMap::new(),
+            views: HashMap::new(),
+            flags: HashMap::new(),
+            choices: HashMap::new(),
+            numbers: HashMap::new(),
+            items: HashMap::new(),
+        }
+    }
+
+    pub fn action(mut self, name: &str, action: Box<dyn Fn()>) -> MenuRegistry {
+        self.actions.insert(name.to_owned(), action.into());
+        self
+    }
+    pub fn view(mut self, name: &str, spawner: Box<dyn ViewSpawner>) -> MenuRegistry {
+        self.views.insert(name.to_owned(), spawner.into());
+        self
+    }
+    /// A value for toggles and checkboxes
+    pub fn flag(mut self, name: &str, flag: Rc<Cell<bool>>) -> MenuRegistry {
+        self.flags.insert(name.to_owned(), flag);
+        self
+    }
+    /// A value for radio groups
+    pub fn choice(mut self, name: &str, choice: Rc<Cell<usize>>) -> MenuRegistry {
+        self.choices.insert(name.to_owned(), choice);
+        self
+    }
+    /// A value for spinners and sliders
+    pub fn number(mut self, name: &str, number: Rc<Cell<f32>>) -> MenuRegistry {
+        self.numbers.insert(name.to_owned(), number);
+        self
+    }
+    /// Entries made each time the menu is built
+    pub fn items(mut self, name: &str, items: Box<dyn Fn() -> Vec<MenuItem>>) -> MenuRegistry {
+        self.items.insert(name.to_owned(), items.into());
+        self
+    }
+}
+
+fn lookup<T: Clone>(map: &HashMap<String, T>, kind: &str, name: &str) -> Result<T, MenuFileError> {
+    map.get(name)
+        .cloned()
+        .ok_or_else(|| MenuFileError::Unregistered(format!("{} \"{}\", which isn't registered", kind, name)))
+}
+
+/// Lets a registered spawner be shared by every menu built from the file
+struct SharedSpawner(Rc<dyn ViewSpawner>);
+
+impl ViewSpawner for SharedSpawner {
+    fn spawn(&self) -> Box<dyn View> { self.0.spawn() }
+}
+
+impl MenuDefinition {
+    pub fn from_json(source: &str) -> Result<MenuDefinition, MenuFileError> {
+        serde_json::from_str(source).map_err(|err| MenuFileError::Parse(err.to_string()))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<MenuDefinition, MenuFileError> {
+        let source = fs::read_to_string(path).map_err(|err| MenuFileError::Read(err.to_string()))?;
+        MenuDefinition::from_json(&source)
+    }
+
+    pub fn build(&self, registry: &MenuRegistry) -> Result<Menu, MenuFileError> {
+        let mut menu = Menu::new().title(self.title.clone());
+        menu.add_entries(build_entries(&self.entries, registry)?);
+        Ok(menu)
+    }
+}
+
+fn build_entries(entries: &[EntryDefinition], registry: &MenuRegistry) -> Result<Vec<MenuItem>, MenuFileError> {
+    let mut items = vec![];
+    for entry in entries {
+        match entry {
+            EntryDefinition::Action { label, action } => {
+                let action = lookup(&registry.actions, "action", action)?;
+                items.push(MenuItem::TextToFunc(label.clone(), Box::new(move || action())));
+            }
+            EntryDefinition::View { label, view } => {
+                let spawner = lookup(&registry.views, "view", view)?;
+                items.push(MenuItem::TextToView(label.clone(), Box::new(SharedSpawner(spawner))));
+            }
+            EntryDefinition::Submenu { label, entries } => {
+                items.push(MenuItem::Submenu(label.clone(), build_entries(entries, registry)?));
+            }
+            EntryDefinition::Toggle { label, key } => {
+                items.push(MenuItem::Toggle(label.clone(), lookup(&registry.flags, "flag", key)?));
+            }
+            EntryDefinition::Checkbox { label, key } => {
+                items.push(MenuItem::Checkbox(label.clone(), lookup(&registry.flags, "flag", key)?));
+            }
+            EntryDefinition::Radio { key, options } => {
+                items.extend(MenuItem::radio_group(options.clone(), lookup(&registry.choices, "choice", key)?));
+            }
+            EntryDefinition::Spinner { label, key, min, max, step, decimals } => {
+                check_range(label, *min, *max, *step)?;
+                let number = NumberEntry::new(lookup(&registry.numbers, "number", key)?, *min, *max, *step)
+                    .decimals(*decimals);
+                items.push(MenuItem::Spinner(label.clone(), number));
+            }
+            EntryDefinition::Slider { label, key, min, max, step } => {
+                check_range(label, *min, *max, *step)?;
+                let number = NumberEntry::new(lookup(&registry.numbers, "number", key)?, *min, *max, *step);
+                items.push(MenuItem::Slider(label.clone(), number));
+            }
+            EntryDefinition::Items { name } => {
+                items.extend(lookup(&registry.items, "items", name)?());
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Catch ranges `NumberEntry` would refuse, so a bad file is an error rather than a panic
+fn check_range(label: &str, min: f32, max: f32, step: f32) -> Result<(), MenuFileError> {
+    if min > max {
+        return Err(MenuFileError::Invalid(format!("'{}' has min {} above max {}", label, min, max)));
+    }
+    if step <= 0.0 {
+        return Err(MenuFileError::Invalid(format!("'{}' has step {}, which must be positive", label, step)));
+    }
+    Ok(())
+}
+
+type ErrorHandler = Box<dyn Fn(&MenuFileError)>;
+
+/// A menu built from a definition file, rebuilt whenever the file changes, keeping
+/// the open submenu and selected entry where their labels are still there.
+/// If the changed file can't be used, the menu stays as it was.
+pub struct MenuFile {
+    menu: Menu,
+    registry: MenuRegistry,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    checked: Instant,
+    error: Option<MenuFileError>,
+    on_error: Option<ErrorHandler>,
+}
+
+impl MenuFile {
+    pub fn load<P: AsRef<Path>>(path: P, registry: MenuRegistry) -> Result<MenuFile, MenuFileError> {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified(&path);
+        let menu = MenuDefinition::load(&path)?.build(&registry)?;
+        Ok(MenuFile::new(menu, registry, Some(path), modified))
+    }
+
+    /// Build the menu from a definition held in memory, which never reloads
+    pub fn from_json(source: &str, registry: MenuRegistry) -> Result<MenuFile, MenuFileError> {
+        let menu = MenuDefinition::from_json(source)?.build(&registry)?;
+        Ok(MenuFile::new(menu, registry, None, None))
+    }
+
+    fn new(menu: Menu, registry: MenuRegistry, path: Option<PathBuf>, modified: Option<SystemTime>) -> MenuFile {
+        MenuFile { menu, registry, path, modified, checked: Instant::now(), error: None, on_error: None }
+    }
+
+    /// Called when the changed file can't be used, so the problem can be shown or logged
+    pub fn on_reload_error(mut self, on_error: Box<dyn Fn(&MenuFileError)>) -> MenuFile {
+        self.on_error = Some(on_error);
+        self
+    }
+
+    /// Why the last change to the file couldn't be used, if it couldn't
+    pub fn last_error(&self) -> Option<&MenuFileError> { self.error.as_ref() }
+
+    /// Rebuild the menu if the file has changed since it was last read
+    fn reload(&mut self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let modified = modified(path);
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+        match MenuDefinition::load(path).and_then(|definition| definition.build(&self.registry)) {
+            Ok(menu) => {
+                let location = self.menu.location();
+                self.menu = menu;
+                self.menu.go_to(&location);
+                self.error = None;
+            }
+            Err(err) => {
+                if let Some(on_error) = &self.on_error {
+                    on_error(&err);
+                }
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl View for MenuFile {
+    fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if self.checked.elapsed() >= RELOAD_CHECK {
+            self.checked = Instant::now();
+            self.reload();
+        }
+        self.menu.update(buttons)
+    }
+    fn render(&self, screen: &mut Screen) { self.menu.render(screen) }
+    fn result(&mut self, result: Box<dyn Any>) { self.menu.result(result) }
+}
@@ -2,7 +2,17 @@ mod boids;
 mod helloworld;
 mod menu;
 mod input;
+mod form;
+mod number;
+mod menu_file;
+mod dynamic;
+mod memory;
 pub use boids::{BoidsView, BoidsViewBuilder};
 pub use helloworld::HelloWorld;
-pub use menu::{CustomMenuEntry, Menu, MenuItem};
-pub use input::TextInput;
\ No newline at end of file
+pub use menu::{CustomMenuEntry, EntryDetails, Menu, MenuItem, ScrollIndicator};
+pub use input::TextInput;
+pub use form::Form;
+pub use number::NumberEntry;
+pub use menu_file::{EntryDefinition, MenuDefinition, MenuFile, MenuFileError, MenuRegistry};
+pub use dynamic::{DynamicMenu, Refresher};
+pub use memory::{MenuMemory, MenuOrder};
@@ -0,0 +1,229 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::buttons::{Button, ButtonSet};
+use crate::screen::Screen;
+use crate::shape::{Bitmap, Drawable, Rect, Text};
+use crate::theme::{Theme, INDICATOR_SIZE};
+
+// steps taken per press as a button is held for longer, moving up every
+// `ACCELERATE_AFTER` repeats
+const ACCELERATION: &[i32] = &[1, 2, 5, 10];
+const ACCELERATE_AFTER: u32 = 5;
+
+const SLIDER_WIDTH: i32 = 40;
+// space between a spinner's arrows and its value
+const ARROW_GAP: i32 = 2;
+/// A small triangle pointing left
+pub(crate) const POINTER: &[u8] = &[
+    0b00100000,
+    0b01100000,
+    0b11100000,
+    0b01100000,
+    0b00100000,
+];
+
+/// A number within a range, drawn as a spinner or a slider in a menu row and
+/// stepped down and up with left and right. Holding either button speeds it up.
+/// Each new value is stored in `value` and passed to `on_change`.
+///
+/// Values are kept to the number of `decimals` shown. With none, the default, the
+/// entry holds whole numbers exactly, for integer settings; give it a whole `step`.
+pub struct NumberEntry {
+    value: Rc<Cell<f32>>,
+    min: f32,
+    max: f32,
+    step: f32,
+    decimals: usize,
+    on_change: Option<Box<dyn Fn(f32)>>,
+    // presses in a row that came from holding a button
+    held: u32,
+}
+
+impl NumberEntry {
+    /// Panics unless `min <= max` and `step` is positive
+    pub fn new(value: Rc<Cell<f32>>, min: f32, max: f32, step: f32) -> NumberEntry {
+        assert!(min <= max, "NumberEntry range {} to {} is empty", min, max);
+        assert!(step > 0.0, "NumberEntry step {} is not positive", step);
+        NumberEntry {
+            value,
+            min, max, step,
+            decimals: 0,
+            on_change: None,
+            held: 0,
+        }
+    }
+
+    /// Decimal places to show and keep, none by default
+    pub fn decimals(mut self, decimals: usize) -> NumberEntry { self.decimals = decimals; self }
+    pub fn on_change(mut self, on_change: Box<dyn Fn(f32)>) -> NumberEntry { self.on_change = Some(on_change); self }
+
+    pub fn get(&self) -> f32 { self.value.get() }
+
+    /// Step the value with any left or right presses, consuming them
+    pub fn update(&mut self, buttons: &mut ButtonSet) {
+        let mut steps = 0;
+        if buttons.left.was_pressed() {
+            steps -= self.acceleration(&buttons.left);
+        }
+        if buttons.right.was_pressed() {
+            steps += self.acceleration(&buttons.right);
+        }
+        if steps == 0 {
+            return;
+        }
+        let stepped = self.value.get() + steps as f32 * self.step;
+        // snap to whole steps from the minimum, so repeated float steps don't drift,
+        // then to the places shown, so integers and short decimals stay exact
+        let snapped = self.min + ((stepped - self.min) / self.step).round() * self.step;
+        let scale = 10f32.powi(self.decimals as i32);
+        let value = ((snapped * scale).round() / scale).max(self.min).min(self.max);
+        if value != self.value.get() {
+            self.value.set(value);
+            if let Some(on_change) = &self.on_change {
+                on_change(value);
+            }
+        }
+    }
+
+    fn acceleration(&mut self, button: &Button) -> i32 {
+        if button.is_held() {
+            self.held += 1;
+        } else {
+            self.held = 0;
+        }
+        let level = (self.held / ACCELERATE_AFTER) as usize;
+        ACCELERATION[level.min(ACCELERATION.len() - 1)]
+    }
+
+    fn format(&self, value: f32) -> String {
+        format!("{:.*}", self.decimals, value)
+    }
+
+    fn text(&self, value: f32, theme: &Theme) -> Text {
+        Text::new(self.format(value)).font(theme.font.clone())
+    }
+
+    /// Width of the value between a spinner's arrows, enough for any value in range
+    fn value_width(&self, theme: &Theme) -> i32 {
+        self.text(self.min, theme).get_width().max(self.text(self.max, theme).get_width())
+    }
+
+    pub(crate) fn spinner_width(&self, theme: &Theme) -> i32 {
+        self.value_width(theme) + 2 * (3 + ARROW_GAP)
+    }
+
+    pub(crate) fn slider_width(&self) -> i32 { SLIDER_WIDTH }
+
+    /// Draw the value right-aligned between arrows, at (x, y) in a row `height` tall
+    pub(crate) fn draw_spinner(&self, x: i32, y: i32, height: i32, selected: bool, theme: &Theme, screen: &mut Screen) {
+        let (fill, stroke) = theme.text_colors(selected);
+        let value = self.value.get();
+        let arrow_y = y + (height - 5 + 1) / 2;
+        let value_width = self.value_width(theme);
+        if value > self.min {
+            Bitmap::new(POINTER, 3, 5)
+                .invert(theme.inverts(selected))
+                .at(x, arrow_y)
+                .draw(screen);
+        }
+        let text = self.text(value, theme);
+        let text_x = x + 3 + ARROW_GAP + value_width - text.get_width();
+        let text_y = y + (height - theme.font.bitmap().line_height() + 1) / 2;
+        text.fill(fill)
+            .stroke(stroke)
+            .draw_at(text_x, text_y, screen);
+        if value < self.max {
+            Bitmap::new(POINTER, 3, 5)
+                .flip_horizontal()
+                .invert(theme.inverts(selected))
+                .at(x + 3 + 2 * ARROW_GAP + value_width, arrow_y)
+                .draw(screen);
+        }
+    }
+
+    /// Draw a bar filled in proportion to where the value sits in its range
+    pub(crate) fn draw_slider(&self, x: i32, y: i32, height: i32, selected: bool, theme: &Theme, screen: &mut Screen) {
+        let (fill, stroke) = theme.text_colors(selected);
+        let y = y + (height - INDICATOR_SIZE + 1) / 2;
+        Rect::new(SLIDER_WIDTH - 1, INDICATOR_SIZE - 1)
+            .at(x, y)
+            .stroke(stroke)
+            .fill(fill)
+            .draw(screen);
+        let range = self.max - self.min;
+        let fraction = if range > 0.0 { (self.value.get() - self.min) / range } else { 1.0 };
+        let filled = (fraction * (SLIDER_WIDTH - 4) as f32).round() as i32;
+        if filled > 0 {
+            Rect::new(filled - 1, INDICATOR_SIZE - 5)
+                .at(x + 2, y + 2)
+                .stroke(stroke)
+                .fill(stroke)
+                .draw(screen);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(entry: &mut NumberEntry, right: bool, held: bool) -> f32 {
+        let mut buttons = ButtonSet::virtual_buttons();
+        let button = if right { &mut buttons.right } else { &mut buttons.left };
+        button.press();
+        button.is_hold = held;
+        entry.update(&mut buttons);
+        entry.get()
+    }
+
+    #[test]
+    fn clamps_to_the_range() {
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(9.0)), 0.0, 10.0, 1.0)
+            .on_change(Box::new(move |_| counter.set(counter.get() + 1)));
+        assert_eq!(step(&mut entry, true, false), 10.0);
+        assert_eq!(step(&mut entry, true, false), 10.0);
+        // staying at the limit isn't a change
+        assert_eq!(changes.get(), 1);
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(0.5)), 0.0, 10.0, 1.0);
+        assert_eq!(step(&mut entry, false, false), 0.0);
+    }
+
+    #[test]
+    fn float_steps_land_on_the_step_grid() {
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(0.0)), 0.0, 1.0, 0.1).decimals(1);
+        for _ in 0..3 {
+            step(&mut entry, true, false);
+        }
+        assert_eq!(entry.get(), 0.3);
+        // a value between steps snaps to the nearest one
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(0.27)), 0.0, 1.0, 0.1).decimals(1);
+        assert_eq!(step(&mut entry, true, false), 0.4);
+    }
+
+    #[test]
+    fn whole_steps_stay_whole() {
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(30.0)), 0.0, 100.0, 3.0);
+        for _ in 0..7 {
+            step(&mut entry, false, false);
+        }
+        assert_eq!(entry.get(), 9.0);
+        // steps count from the minimum, so 0 is between steps from -100
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(0.0)), -100.0, 100.0, 3.0);
+        assert_eq!(step(&mut entry, false, false), -4.0);
+    }
+
+    #[test]
+    fn holding_speeds_up_and_letting_go_resets() {
+        let mut entry = NumberEntry::new(Rc::new(Cell::new(0.0)), 0.0, 1000.0, 1.0);
+        let stepped: Vec<f32> = (0..20).map(|_| step(&mut entry, true, true)).collect();
+        // 4 repeats of 1, then 5 each of 2 and 5, then 10 at a time
+        assert_eq!(stepped[3], 4.0);
+        assert_eq!(stepped[8], 14.0);
+        assert_eq!(stepped[13], 39.0);
+        assert_eq!(stepped[19], 99.0);
+        assert_eq!(step(&mut entry, true, false), 100.0);
+    }
+}
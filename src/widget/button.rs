@@ -0,0 +1,64 @@
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::shape::{Align, Drawable, Rect, Text};
+use crate::theme::Theme;
+use crate::view::{UpdateResult, ViewSpawner};
+use crate::widget::{row_height, text_y, Widget};
+
+enum Action {
+    Func(Box<dyn Fn()>),
+    View(Box<dyn ViewSpawner>),
+}
+
+/// A labelled button that runs a function or opens a view when A is pressed
+pub struct Button {
+    label: String,
+    action: Action,
+}
+
+impl Button {
+    pub fn new(label: String, func: Box<dyn Fn()>) -> Button {
+        Button { label, action: Action::Func(func) }
+    }
+
+    pub fn to_view(label: String, spawner: Box<dyn ViewSpawner>) -> Button {
+        Button { label, action: Action::View(spawner) }
+    }
+
+    fn text(&self, theme: &Theme) -> Text {
+        Text::new(self.label.clone()).font(theme.font.clone()).emoji(true)
+    }
+}
+
+impl Widget for Button {
+    fn render(&self, x: i32, y: i32, width: i32, height: i32, focused: bool, screen: &mut Screen) {
+        let theme = screen.theme().clone();
+        Rect::new(width - 1, height - 1)
+            .at(x, y)
+            .draw(screen);
+        theme.draw_selection(x, y, width - 1, height - 1, focused, screen);
+        let (fill, stroke) = theme.text_colors(focused);
+        self.text(&theme)
+            .fill(fill)
+            .stroke(stroke)
+            .max_width(width - 2 * theme.padding)
+            .ellipsis(true)
+            .align(Align::Center)
+            .draw_at(x + theme.padding, y + text_y(height, &theme), screen);
+    }
+    fn get_width(&self, theme: &Theme) -> i32 { self.text(theme).get_width() + 2 * theme.padding }
+    fn get_height(&self, theme: &Theme) -> i32 { row_height(theme) }
+
+    fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if !buttons.a.was_pressed() {
+            return None;
+        }
+        match &self.action {
+            Action::Func(func) => {
+                func();
+                None
+            }
+            Action::View(spawner) => Some(UpdateResult::NewView(spawner.spawn())),
+        }
+    }
+}
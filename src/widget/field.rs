@@ -0,0 +1,68 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::shape::{Drawable, Line, Text};
+use crate::theme::Theme;
+use crate::view::UpdateResult;
+use crate::views::TextInput;
+use crate::widget::{row_height, text_y, Widget};
+
+/// A line of text edited on the on-screen keyboard, opened with A. Shows
+/// `placeholder` while empty. Whatever the keyboard gives back replaces `text`.
+pub struct Field {
+    text: Rc<RefCell<String>>,
+    placeholder: String,
+}
+
+impl Field {
+    pub fn new(text: Rc<RefCell<String>>) -> Field {
+        Field { text, placeholder: String::new() }
+    }
+
+    pub fn placeholder(mut self, placeholder: String) -> Field { self.placeholder = placeholder; self }
+
+    fn shown(&self) -> String {
+        let text = self.text.borrow();
+        if text.is_empty() { self.placeholder.clone() } else { text.clone() }
+    }
+}
+
+impl Widget for Field {
+    fn render(&self, x: i32, y: i32, width: i32, height: i32, focused: bool, screen: &mut Screen) {
+        let theme = screen.theme().clone();
+        theme.draw_selection(x, y, width - 1, height - 1, focused, screen);
+        let (fill, stroke) = theme.text_colors(focused);
+        Text::new(self.shown())
+            .font(theme.font.clone())
+            .fill(fill)
+            .stroke(stroke)
+            .max_width(width - 2 * theme.padding)
+            .ellipsis(true)
+            .draw_at(x + theme.padding, y + text_y(height, &theme), screen);
+        Line::new(width - 1 - 2 * theme.padding, 0)
+            .at(x + theme.padding, y + height - 2)
+            .stroke(stroke)
+            .draw(screen);
+    }
+    fn get_width(&self, theme: &Theme) -> i32 {
+        Text::new(self.shown()).font(theme.font.clone()).get_width() + 2 * theme.padding
+    }
+    fn get_height(&self, theme: &Theme) -> i32 { row_height(theme) }
+
+    fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if buttons.a.was_pressed() {
+            Some(UpdateResult::NewView(Box::new(TextInput::new().text(self.text.borrow().clone()))))
+        } else {
+            None
+        }
+    }
+
+    fn result(&mut self, result: Box<dyn Any>) {
+        if let Ok(text) = result.downcast::<String>() {
+            *self.text.borrow_mut() = *text;
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::shape::Drawable;
+use crate::theme::Theme;
+use crate::view::UpdateResult;
+use crate::widget::Widget;
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A widget along with what its place in the layout shares with the manager
+struct Entry {
+    widget: RefCell<Box<dyn Widget>>,
+    // where the widget was last drawn, if it has been
+    bounds: Cell<Option<(i32, i32, i32, i32)>>,
+    focused: Cell<bool>,
+    // the theme of the frame being drawn, for measuring the widget
+    theme: Rc<RefCell<Theme>>,
+}
+
+/// Stands in for a widget within a layout, drawing it and noting where it went
+struct Slot(Rc<Entry>);
+
+impl Drawable for Slot {
+    fn draw(&self, screen: &mut Screen) { self.draw_at(0, 0, screen) }
+    fn draw_at(&self, x: i32, y: i32, screen: &mut Screen) {
+        self.draw_in(x, y, self.get_width(), self.get_height(), screen)
+    }
+    fn draw_in(&self, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        self.0.bounds.set(Some((x, y, width, height)));
+        self.0.widget.borrow().render(x, y, width, height, self.0.focused.get(), screen);
+    }
+    fn get_x(&self) -> i32 { 0 }
+    fn get_y(&self) -> i32 { 0 }
+    fn get_width(&self) -> i32 { self.0.widget.borrow().get_width(&self.0.theme.borrow()) }
+    fn get_height(&self) -> i32 { self.0.widget.borrow().get_height(&self.0.theme.borrow()) }
+}
+
+/// Owns a set of widgets, moves focus between them with the D-pad, and routes
+/// the other buttons to the focused one.
+///
+/// Focus moves to the nearest focusable widget in the direction pressed, by where
+/// the widgets were drawn, so it follows whatever shape the layout takes.
+pub struct FocusManager {
+    entries: Vec<Rc<Entry>>,
+    focused: Option<usize>,
+    theme: Rc<RefCell<Theme>>,
+}
+
+impl Default for FocusManager {
+    fn default() -> FocusManager { FocusManager::new() }
+}
+
+impl FocusManager {
+    pub fn new() -> FocusManager {
+        FocusManager {
+            entries: vec![],
+            focused: None,
+            theme: Rc::new(RefCell::new(Theme::default())),
+        }
+    }
+
+    /// Take charge of a widget, giving back what to place in a layout to show it
+    pub fn add(&mut self, widget: Box<dyn Widget>) -> Box<dyn Drawable> {
+        let entry = Rc::new(Entry {
+            widget: RefCell::new(widget),
+            bounds: Cell::new(None),
+            focused: Cell::new(false),
+            theme: self.theme.clone(),
+        });
+        self.entries.push(entry.clone());
+        Box::new(Slot(entry))
+    }
+
+    /// The index, in the order they were added, of the focused widget
+    pub fn focused(&self) -> Option<usize> { self.focused }
+
+    /// Move focus to the widget added `index`th, if it can take it
+    pub fn focus(&mut self, index: usize) {
+        if self.entries.get(index).is_some_and(|entry| entry.widget.borrow().focusable()) {
+            self.set_focus(Some(index));
+        }
+    }
+
+    fn set_focus(&mut self, index: Option<usize>) {
+        if let Some(old) = self.focused {
+            self.entries[old].focused.set(false);
+        }
+        if let Some(new) = index {
+            self.entries[new].focused.set(true);
+        }
+        self.focused = index;
+    }
+
+    /// Give the focused widget first go at the buttons, then move focus with
+    /// any D-pad presses it left
+    pub fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if self.focused.is_none() {
+            let first = self.entries.iter().position(|entry| entry.widget.borrow().focusable());
+            self.set_focus(first);
+        }
+        if let Some(index) = self.focused {
+            if let Some(result) = self.entries[index].widget.borrow_mut().update(buttons) {
+                return Some(result);
+            }
+        }
+
+        let pressed = [
+            (buttons.up.was_pressed(), Direction::Up),
+            (buttons.down.was_pressed(), Direction::Down),
+            (buttons.left.was_pressed(), Direction::Left),
+            (buttons.right.was_pressed(), Direction::Right),
+        ];
+        for &(_, direction) in pressed.iter().filter(|(pressed, _)| *pressed) {
+            if let Some(next) = self.nearest(direction) {
+                self.set_focus(Some(next));
+            }
+        }
+        None
+    }
+
+    /// Pass the result of a view to the focused widget, which will have opened it
+    pub fn result(&mut self, result: Box<dyn Any>) {
+        if let Some(index) = self.focused {
+            self.entries[index].widget.borrow_mut().result(result);
+        }
+    }
+
+    /// Draw `root`, the layout holding the widgets, into the given box
+    pub fn render(&self, root: &dyn Drawable, x: i32, y: i32, width: i32, height: i32, screen: &mut Screen) {
+        *self.theme.borrow_mut() = screen.theme().clone();
+        root.draw_in(x, y, width, height, screen);
+    }
+
+    /// The focusable widget closest to the focused one in `direction`. Distance
+    /// sideways counts for more than distance ahead, so focus keeps to its row
+    /// or column where it can.
+    fn nearest(&self, direction: Direction) -> Option<usize> {
+        let from = self.focused.and_then(|index| self.entries[index].bounds.get())?;
+        let (from_x, from_y) = centre(from);
+        self.entries.iter()
+            .enumerate()
+            .filter(|(index, entry)| Some(*index) != self.focused && entry.widget.borrow().focusable())
+            .filter_map(|(index, entry)| {
+                let (x, y) = centre(entry.bounds.get()?);
+                let (ahead, aside) = match direction {
+                    Direction::Up => (from_y - y, x - from_x),
+                    Direction::Down => (y - from_y, x - from_x),
+                    Direction::Left => (from_x - x, y - from_y),
+                    Direction::Right => (x - from_x, y - from_y),
+                };
+                if ahead > 0 { Some((ahead + 2 * aside.abs(), index)) } else { None }
+            })
+            .min()
+            .map(|(_, index)| index)
+    }
+}
+
+fn centre((x, y, width, height): (i32, i32, i32, i32)) -> (i32, i32) {
+    (x + width / 2, y + height / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::PanelSize;
+    use crate::layout::Grid;
+    use crate::shape::Rotation;
+
+    struct Square {
+        focusable: bool,
+        presses: Rc<Cell<u32>>,
+    }
+
+    impl Widget for Square {
+        fn render(&self, _x: i32, _y: i32, _width: i32, _height: i32, _focused: bool, _screen: &mut Screen) {}
+        fn get_width(&self, _theme: &Theme) -> i32 { 10 }
+        fn get_height(&self, _theme: &Theme) -> i32 { 10 }
+        fn focusable(&self) -> bool { self.focusable }
+        fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+            if buttons.a.was_pressed() {
+                self.presses.set(self.presses.get() + 1);
+            }
+            None
+        }
+    }
+
+    /// Two rows of three squares, drawn once so the manager knows where they are
+    fn grid(focusable: &[bool]) -> (FocusManager, Vec<Rc<Cell<u32>>>) {
+        let mut manager = FocusManager::new();
+        let mut root = Grid::new(3);
+        let mut presses = vec![];
+        for &focusable in focusable {
+            let count = Rc::new(Cell::new(0));
+            root.push(manager.add(Box::new(Square { focusable, presses: count.clone() })));
+            presses.push(count);
+        }
+        let mut screen = Screen::headless(PanelSize::Panel128x64, Rotation::Deg0);
+        manager.render(&root, 0, 0, 128, 64, &mut screen);
+        (manager, presses)
+    }
+
+    fn press(manager: &mut FocusManager, button: fn(&mut ButtonSet) -> &mut crate::buttons::Button) -> Option<usize> {
+        let mut buttons = ButtonSet::virtual_buttons();
+        button(&mut buttons).press();
+        manager.update(&mut buttons);
+        manager.focused()
+    }
+
+    #[test]
+    fn moves_to_the_nearest_widget_each_way() {
+        let (mut manager, _) = grid(&[true; 6]);
+        assert_eq!(press(&mut manager, |b| &mut b.c), Some(0));
+        assert_eq!(press(&mut manager, |b| &mut b.right), Some(1));
+        assert_eq!(press(&mut manager, |b| &mut b.down), Some(4));
+        assert_eq!(press(&mut manager, |b| &mut b.left), Some(3));
+        assert_eq!(press(&mut manager, |b| &mut b.up), Some(0));
+    }
+
+    #[test]
+    fn stays_put_with_nothing_that_way() {
+        let (mut manager, _) = grid(&[true; 6]);
+        assert_eq!(press(&mut manager, |b| &mut b.left), Some(0));
+        assert_eq!(press(&mut manager, |b| &mut b.up), Some(0));
+        manager.focus(5);
+        assert_eq!(press(&mut manager, |b| &mut b.right), Some(5));
+        assert_eq!(press(&mut manager, |b| &mut b.down), Some(5));
+    }
+
+    #[test]
+    fn skips_widgets_that_cant_take_focus() {
+        let (mut manager, _) = grid(&[false, true, false, true, false, true]);
+        assert_eq!(press(&mut manager, |b| &mut b.c), Some(1));
+        assert_eq!(press(&mut manager, |b| &mut b.down), Some(3));
+        // one column over and a row up is nearer than two columns over on the same row
+        assert_eq!(press(&mut manager, |b| &mut b.right), Some(1));
+        manager.focus(4);
+        assert_eq!(manager.focused(), Some(1));
+    }
+
+    #[test]
+    fn a_goes_to_the_focused_widget_and_b_is_left_alone() {
+        let (mut manager, presses) = grid(&[true; 6]);
+        manager.focus(2);
+        press(&mut manager, |b| &mut b.a);
+        let counts: Vec<u32> = presses.iter().map(|count| count.get()).collect();
+        assert_eq!(counts, [0, 0, 1, 0, 0, 0]);
+
+        let mut buttons = ButtonSet::virtual_buttons();
+        buttons.b.press();
+        assert!(manager.update(&mut buttons).is_none());
+        assert!(buttons.b.has_press());
+    }
+}
@@ -0,0 +1,30 @@
+use crate::screen::Screen;
+use crate::shape::{Drawable, Text};
+use crate::theme::Theme;
+use crate::widget::{row_height, text_y, Widget};
+
+/// Text that focus passes over
+pub struct Label {
+    text: String,
+}
+
+impl Label {
+    pub fn new(text: String) -> Label { Label { text } }
+
+    fn text(&self, theme: &Theme) -> Text {
+        Text::new(self.text.clone()).font(theme.font.clone()).emoji(true)
+    }
+}
+
+impl Widget for Label {
+    fn render(&self, x: i32, y: i32, width: i32, height: i32, _focused: bool, screen: &mut Screen) {
+        let theme = screen.theme().clone();
+        self.text(&theme)
+            .max_width(width - 2 * theme.padding)
+            .ellipsis(true)
+            .draw_at(x + theme.padding, y + text_y(height, &theme), screen);
+    }
+    fn get_width(&self, theme: &Theme) -> i32 { self.text(theme).get_width() + 2 * theme.padding }
+    fn get_height(&self, theme: &Theme) -> i32 { row_height(theme) }
+    fn focusable(&self) -> bool { false }
+}
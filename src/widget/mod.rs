@@ -0,0 +1,45 @@
+//! Widgets that keep their state between frames, placed in layouts and given
+//! focus by a `FocusManager`. Build a screen out of them with `views::Form`
+//! rather than writing a new `View` for each one.
+
+mod button;
+mod field;
+mod focus;
+mod label;
+mod toggle;
+
+pub use button::Button;
+pub use field::Field;
+pub use focus::FocusManager;
+pub use label::Label;
+pub use toggle::Toggle;
+
+use std::any::Any;
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::theme::Theme;
+use crate::view::UpdateResult;
+
+pub trait Widget {
+    /// Draw into the `width` x `height` box the layout gave the widget
+    fn render(&self, x: i32, y: i32, width: i32, height: i32, focused: bool, screen: &mut Screen);
+    /// The size the widget would like, for layouts to fit it
+    fn get_width(&self, theme: &Theme) -> i32;
+    fn get_height(&self, theme: &Theme) -> i32;
+    /// Whether focus can land on the widget
+    fn focusable(&self) -> bool { true }
+    /// Handle input while focused. Presses left unconsumed go on to move the focus
+    /// or leave the form.
+    fn update(&mut self, _buttons: &mut ButtonSet) -> Option<UpdateResult> { None }
+    /// Take the result of a view the widget opened
+    fn result(&mut self, _result: Box<dyn Any>) {}
+}
+
+/// Height of a single line widget: a menu row
+fn row_height(theme: &Theme) -> i32 { theme.row_height + 1 }
+
+/// Offset of text from the top of a widget `height` pixels tall, centring it
+fn text_y(height: i32, theme: &Theme) -> i32 {
+    (height - theme.font.bitmap().line_height()) / 2
+}
@@ -0,0 +1,54 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::buttons::ButtonSet;
+use crate::screen::Screen;
+use crate::shape::{Drawable, Text};
+use crate::theme::{Theme, INDICATOR_SIZE};
+use crate::view::UpdateResult;
+use crate::widget::{row_height, text_y, Widget};
+
+/// A labelled on/off switch, flipped with A and drawn with a check box at its
+/// right edge. Flipping it writes straight to `state`.
+pub struct Toggle {
+    label: String,
+    state: Rc<Cell<bool>>,
+}
+
+impl Toggle {
+    pub fn new(label: String, state: Rc<Cell<bool>>) -> Toggle {
+        Toggle { label, state }
+    }
+
+    fn text(&self, theme: &Theme) -> Text {
+        Text::new(self.label.clone()).font(theme.font.clone()).emoji(true)
+    }
+}
+
+impl Widget for Toggle {
+    fn render(&self, x: i32, y: i32, width: i32, height: i32, focused: bool, screen: &mut Screen) {
+        let theme = screen.theme().clone();
+        theme.draw_selection(x, y, width - 1, height - 1, focused, screen);
+        let (fill, stroke) = theme.text_colors(focused);
+        let check_x = x + width - theme.padding - INDICATOR_SIZE;
+        self.text(&theme)
+            .fill(fill)
+            .stroke(stroke)
+            .max_width(check_x - x - 2 * theme.padding)
+            .ellipsis(true)
+            .draw_at(x + theme.padding, y + text_y(height, &theme), screen);
+
+        theme.draw_check(check_x, y + (height - INDICATOR_SIZE) / 2, self.state.get(), focused, screen);
+    }
+    fn get_width(&self, theme: &Theme) -> i32 {
+        self.text(theme).get_width() + 3 * theme.padding + INDICATOR_SIZE
+    }
+    fn get_height(&self, theme: &Theme) -> i32 { row_height(theme) }
+
+    fn update(&mut self, buttons: &mut ButtonSet) -> Option<UpdateResult> {
+        if buttons.a.was_pressed() {
+            self.state.set(!self.state.get());
+        }
+        None
+    }
+}